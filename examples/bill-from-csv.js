@@ -0,0 +1,188 @@
+// Parse -> bill -> report: read an hourly CSV, bill it under every plan, and print
+// a month-by-month table with the cheapest plan.
+//
+//   node examples/bill-from-csv.js usage.csv
+//   node examples/bill-from-csv.js data/*.csv
+//   unzip -p export.zip usage.csv | node examples/bill-from-csv.js -
+//   curl -s https://example.com/api/usage | node examples/bill-from-csv.js --format json -
+//   node examples/bill-from-csv.js --pipeline cleanup.json usage.csv
+//   node examples/bill-from-csv.js --timezone UTC utc-export.csv
+//   node examples/bill-from-csv.js --fill-gaps previous-day-same-hour usage.csv
+//
+// The CSV needs a timestamp column ("YYYY-MM-DD HH:MM", local time) and a kWh column,
+// like a GA Power hourly export saved as CSV. Several files, such as monthly downloads, are
+// merged; hours found in more than one are counted once, with a warning, and so is an hour
+// repeated within a file other than the one clocks repeat in November. --dedupe picks which
+// reading counts: first (the default), last, max, sum, or error to stop. "-" reads standard
+// input. .json and .jsonl files are read as { "timestamp", "kwh" } records (see
+// RateBilling.parseUsageJson); --format json or --format csv overrides the file extension.
+// CSV files from other tools work too: the delimiter (comma, semicolon, tab or pipe) is detected,
+// or set with --delimiter, and --timestamp-column "Interval End" --kwh-column "Usage (kWh)" pick
+// the columns by header name instead of reading the first two.
+// Timestamps are taken as the start of each interval unless they look like interval ends (a
+// column named "... End", or readings running from 01:00 to midnight); --interval-label start
+// or end settles it.
+// Clock times are taken as Georgia time, daylight saving included; --timezone UTC (or another
+// IANA name such as America/Chicago, or Etc/GMT+5 for Eastern Standard Time all year) reads them
+// in that zone and converts them. JSON timestamps with a Z or an offset are exact either way.
+// With fewer than 28 days of summer usage no plan is recommended, since summer on-peak prices
+// make most of the difference between plans; --min-summer-days sets the number (0 turns it off).
+// --pipeline runs a pipeline file's preprocessing steps first (see RateBilling.applyPipeline) and
+// lists what each step did.
+// Missing readings are reported and left out; --fill-gaps zero, previous-day-same-hour, linear
+// or monthly-average fills them in (see RateBilling.fillGaps), and months holding filled
+// readings are marked * in the table.
+// Without a file, a sample year is billed.
+const fs = require('fs');
+const RateBilling = require('../web/billing.js');
+const { sampleYear, readUsageText } = require('./sample-usage.js');
+
+const args = process.argv.slice(2);
+const option = name => {
+    const at = args.indexOf(name);
+    return at === -1 ? null : args.splice(at, 2)[1];
+};
+const format = option('--format');
+if (format !== null && format !== 'json' && format !== 'csv') {
+    console.error('--format must be json or csv');
+    process.exit(1);
+}
+const pipelineFile = option('--pipeline');
+const gapFill = option('--fill-gaps');
+if (gapFill !== null && !RateBilling.GAP_FILL_METHODS[gapFill]) {
+    console.error(`--fill-gaps must be ${Object.keys(RateBilling.GAP_FILL_METHODS).join(', ')}`);
+    process.exit(1);
+}
+const timeZone = option('--timezone');
+const intervalLabel = option('--interval-label') || 'auto';
+if (!['start', 'end', 'auto'].includes(intervalLabel)) {
+    console.error('--interval-label must be start, end or auto');
+    process.exit(1);
+}
+const DELIMITERS = { comma: ',', semicolon: ';', tab: '\t', pipe: '|' };
+const delimiter = option('--delimiter');
+if (delimiter !== null && !DELIMITERS[delimiter]) {
+    console.error(`--delimiter must be ${Object.keys(DELIMITERS).join(', ')}`);
+    process.exit(1);
+}
+const csvOptions = {
+    timestampColumn: option('--timestamp-column'),
+    kwhColumn: option('--kwh-column'),
+    delimiter: DELIMITERS[delimiter]
+};
+const minSummerText = option('--min-summer-days');
+const minSummerDays = minSummerText === null ? RateBilling.MIN_SUMMER_DAYS : Number(minSummerText);
+if (!(Number.isInteger(minSummerDays) && minSummerDays >= 0)) {
+    console.error('--min-summer-days must be a whole number of days, 0 or more');
+    process.exit(1);
+}
+const dedupe = option('--dedupe') || 'first';
+if (!RateBilling.DEDUPE_POLICIES[dedupe]) {
+    console.error(`--dedupe must be ${Object.keys(RateBilling.DEDUPE_POLICIES).join(', ')}`);
+    process.exit(1);
+}
+const files = args;
+const parse = file => ((format || (/\.jsonl?(\.(gz|zst))?$/i.test(file) ? 'json' : 'csv')) === 'json'
+    ? RateBilling.parseUsageJson
+    : text => RateBilling.parseUsageCsv(text, csvOptions))(readUsageText(file));
+let parsed;
+try {
+    parsed = files.map(parse);
+} catch (err) {
+    console.error(err.message);
+    process.exit(1);
+}
+let merged = sampleYear();
+let overlap = null;
+if (files.length > 0) {
+    try {
+        ({ series: merged, overlap } = RateBilling.mergeUsage(parsed, { policy: dedupe, timeZone: timeZone || RateBilling.GEORGIA_TIME_ZONE }));
+    } catch (err) {
+        console.error(err.message);
+        process.exit(1);
+    }
+}
+// Clock times to Georgia time, which the TOU periods are written in
+let georgia = merged;
+if (files.length > 0) {
+    try {
+        const moved = RateBilling.toGeorgiaTime(merged, timeZone || RateBilling.GEORGIA_TIME_ZONE);
+        georgia = moved.series;
+        if (moved.skipped > 0) {
+            console.warn(timeZone
+                ? `${moved.skipped} reading(s) are timed in an hour ${timeZone} clocks skip; read as the hour after.\n`
+                : `${moved.skipped} reading(s) are timed in the hour Georgia clocks skip in March; if the file is in UTC or standard time all year, pass --timezone.\n`);
+        }
+    } catch (err) {
+        console.error(`Unknown --timezone ${timeZone}; use an IANA name such as UTC or America/Chicago`);
+        process.exit(1);
+    }
+}
+// Readings labeled by the end of their interval are moved back to its start
+const label = intervalLabel === 'auto'
+    ? RateBilling.detectIntervalLabel(georgia, { header: csvOptions.timestampColumn || '' })
+    : { label: intervalLabel, reason: '--interval-label' };
+let series = label.label === 'end' ? RateBilling.toIntervalStart(georgia) : georgia;
+if (label.label === 'end' && intervalLabel === 'auto') {
+    console.warn(`Timestamps look like interval ends (${label.reason}); moved back one interval. Use --interval-label start to keep them.\n`);
+}
+if (pipelineFile) {
+    try {
+        const { series: piped, log } = RateBilling.applyPipeline(series, JSON.parse(fs.readFileSync(pipelineFile, 'utf8')));
+        series = piped;
+        console.warn(`Pipeline ${pipelineFile}:\n${log.map((l, i) => `  ${i + 1}. ${l.step}: ${l.detail}`).join('\n')}\n`);
+    } catch (err) {
+        console.error(`Could not run ${pipelineFile}: ${err.message}`);
+        process.exit(1);
+    }
+}
+if (series.length === 0) {
+    console.error(`No hourly readings found in ${files.map(f => f === '-' ? 'standard input' : f).join(', ')}`);
+    process.exit(1);
+}
+const { gaps } = RateBilling.findGaps(series);
+if (gaps.length > 0) {
+    const missing = gaps.reduce((n, g) => n + g.missing, 0);
+    if (gapFill) {
+        series = RateBilling.fillGaps(series, gapFill).series;
+        console.warn(`${missing} missing reading(s) in ${gaps.length} gap(s) filled ${RateBilling.GAP_FILL_METHODS[gapFill]}.\n`);
+    } else {
+        console.warn(`${missing} reading(s) missing in ${gaps.length} gap(s); those months bill low. Use --fill-gaps to estimate them.\n`);
+    }
+}
+if (overlap) {
+    const stamp = dt => `${dt.getFullYear()}-${String(dt.getMonth() + 1).padStart(2, '0')}-${String(dt.getDate()).padStart(2, '0')} ` +
+        `${String(dt.getHours()).padStart(2, '0')}:${String(dt.getMinutes()).padStart(2, '0')}`;
+    console.warn(`Warning: ${overlap.count} repeated reading(s) from ${stamp(overlap.from)} to ${stamp(overlap.to)} were resolved, ` +
+        `${RateBilling.DEDUPE_POLICIES[dedupe].describe}` + (overlap.conflicts > 0 ? ` (${overlap.conflicts} had a different kWh)` : '') + '\n');
+}
+
+const bills = RateBilling.calculateBills(series);
+const { monthlyBills, totals } = bills;
+const filledMonths = new Set(series.filter(r => r.filled).map(r => `${r.dt.getFullYear()}-${String(r.dt.getMonth() + 1).padStart(2, '0')}`));
+const names = RateBilling.PLAN_IDS.map(id => RateBilling.RATE_PLANS[id].name);
+const pad = value => String(value).padStart(10);
+
+console.log(['Month'.padEnd(8), ...names.map(pad)].join(''));
+Object.keys(monthlyBills).forEach(key => {
+    console.log([(filledMonths.has(key) ? `${key}*` : key).padEnd(8), ...RateBilling.PLAN_IDS.map(id => pad(monthlyBills[key][id].total.toFixed(2)))].join(''));
+});
+console.log(['Total'.padEnd(8), ...RateBilling.PLAN_IDS.map(id => pad(totals[id].total.toFixed(2)))].join(''));
+if (filledMonths.size > 0) console.log(`* includes readings filled in for gaps${gapFill ? ` ${RateBilling.GAP_FILL_METHODS[gapFill]}` : ' by the pipeline'}`);
+
+const cheapest = [...RateBilling.PLAN_IDS].sort((a, b) => totals[a].total - totals[b].total)[0];
+const savings = totals['r30'].total - totals[cheapest].total;
+console.log(cheapest === 'r30'
+    ? '\nCheapest: R-30, the standard plan'
+    : `\nCheapest: ${RateBilling.RATE_PLANS[cheapest].name}, $${savings.toFixed(2)} less than R-30`);
+const summer = RateBilling.checkSummerCoverage(bills.monthlyUsage, minSummerDays);
+if (!summer.enough) {
+    console.log(`Not a recommendation: only ${summer.days} day(s) of summer usage, and at least ${summer.minDays} are needed ` +
+        'to compare plans (--min-summer-days). Summer on-peak prices make most of the difference between them.');
+    process.exit(0);
+}
+// What sets it apart: against R-30, or against the runner-up when R-30 is cheapest
+const versus = cheapest === 'r30' ? [...RateBilling.PLAN_IDS].sort((a, b) => totals[a].total - totals[b].total)[1] : 'r30';
+RateBilling.costDrivers(bills, cheapest, versus).forEach(d => {
+    console.log(`  - ${d.text}: ${d.amount < 0 ? 'saves' : 'adds'} $${Math.abs(d.amount).toFixed(2)} against ${RateBilling.RATE_PLANS[versus].name}`);
+});
@@ -0,0 +1,85 @@
+// Clock and calendar checks: bill the day the clocks fall back, timed both ways a file can, and
+// make sure the hour that repeats is read as two hours rather than one hour used twice; then
+// count days and years across February 29. Exits 1 on a failure.
+//
+//   node examples/clock-checks.js
+const assert = require('assert');
+const RateBilling = require('../web/billing.js');
+
+let failures = 0;
+function check(label, fn) {
+    try {
+        fn();
+        console.log(`ok    ${label}`);
+    } catch (err) {
+        failures++;
+        console.log(`FAIL  ${label}: ${err.message}`);
+    }
+}
+
+// 2024-11-03 in half hours, 0.2 kWh each except 1.5 kWh in both passes through 1 AM, so the
+// highest hour is 3 kWh whichever pass it is
+const fallBack = [];
+for (let minutes = 0; minutes < 25 * 60; minutes += 30) {
+    const instant = Date.UTC(2024, 10, 3, 4) + minutes * 60000; // midnight EDT
+    const wall = new Date(instant - (instant < Date.UTC(2024, 10, 3, 6) ? 4 : 5) * 3600000);
+    const dt = new Date(wall.getUTCFullYear(), wall.getUTCMonth(), wall.getUTCDate(), wall.getUTCHours(), wall.getUTCMinutes());
+    fallBack.push({ instant, dt, kwh: dt.getHours() === 1 ? 1.5 : 0.2 });
+}
+
+function demandKw(series) {
+    return RateBilling.aggregateMonthly(series)['2024-11'].plans['tou-rd'].demandKw;
+}
+
+// Clock times as a Georgia file has them, sorted the way the page sorts them
+function asGeorgiaFile() {
+    return fallBack.map(({ dt, kwh }) => ({ dt, kwh })).sort((a, b) => a.dt - b.dt);
+}
+
+check('Georgia clock times: TOU-RD demand is 3 kW', () => {
+    const { series } = RateBilling.toGeorgiaTime(asGeorgiaFile(), RateBilling.GEORGIA_TIME_ZONE);
+    assert.strictEqual(series.filter(r => r.repeated).length, 2);
+    assert.strictEqual(Math.round(demandKw(series) * 1000) / 1000, 3);
+});
+
+check('Exact instants (Z or offset timestamps): TOU-RD demand is 3 kW', () => {
+    const { series } = RateBilling.toGeorgiaTime(fallBack.map(({ instant, kwh }) => ({ dt: new Date(instant), kwh, zoned: true })), 'UTC');
+    assert.strictEqual(series.filter(r => r.repeated).length, 2);
+    assert.strictEqual(Math.round(demandKw(series) * 1000) / 1000, 3);
+});
+
+check('Georgia clock times: the 50 half hours keep all 15.2 kWh', () => {
+    const { series } = RateBilling.toGeorgiaTime(asGeorgiaFile(), RateBilling.GEORGIA_TIME_ZONE);
+    assert.strictEqual(series.length, 50);
+    assert.strictEqual(Math.round(RateBilling.aggregateMonthly(series)['2024-11'].total * 10) / 10, 15.2);
+});
+
+// Leap days: a year from February 29 ends on February 28, and 2024 has 366 days
+const day = (y, m, d) => new Date(y, m - 1, d);
+const iso = dt => `${dt.getFullYear()}-${String(dt.getMonth() + 1).padStart(2, '0')}-${String(dt.getDate()).padStart(2, '0')}`;
+
+check('addYears(2024-02-29, 1) is 2025-02-28', () => {
+    assert.strictEqual(iso(RateBilling.addYears(day(2024, 2, 29), 1)), '2025-02-28');
+    assert.strictEqual(iso(RateBilling.addYears(day(2024, 2, 29), -1)), '2023-02-28');
+});
+
+// Years are counted back from the end, so a year ending 2025-02-28 starts 2024-02-28
+check('fullYearsBetween across February 29', () => {
+    assert.strictEqual(RateBilling.fullYearsBetween(day(2024, 2, 28), day(2025, 2, 28)), 1);
+    assert.strictEqual(RateBilling.fullYearsBetween(day(2024, 2, 29), day(2025, 2, 28)), 0);
+    assert.strictEqual(RateBilling.fullYearsBetween(day(2024, 2, 29), day(2025, 3, 1)), 1);
+    assert.strictEqual(RateBilling.fullYearsBetween(day(2023, 3, 1), day(2024, 2, 29)), 0);
+    assert.strictEqual(RateBilling.fullYearsBetween(day(2023, 3, 1), day(2024, 3, 1)), 1);
+});
+
+check('daysBetween across 2024', () => {
+    assert.strictEqual(RateBilling.daysBetween(day(2024, 1, 1), day(2025, 1, 1)), 366);
+    assert.strictEqual(RateBilling.daysBetween(day(2024, 2, 28), day(2024, 3, 1)), 2);
+    assert.strictEqual(RateBilling.daysBetween(day(2024, 3, 10), day(2024, 11, 3)), 238);
+    assert.strictEqual(RateBilling.daysBetween(day(2025, 1, 1), day(2026, 1, 1)), 365);
+});
+
+if (failures > 0) {
+    console.log(`${failures} check(s) failed`);
+    process.exit(1);
+}
@@ -0,0 +1,34 @@
+// Custom tariff: start from the built-in rates, raise the TOU-REO prices and move its
+// peak to 3-8 PM, then compare the yearly totals before and after.
+//
+//   node examples/custom-tariff.js [rates.json]
+//
+// Pass a rates file (the layout Analysis Options > Rates downloads) to price it instead.
+const fs = require('fs');
+const RateBilling = require('../web/billing.js');
+const { sampleYear } = require('./sample-usage.js');
+
+const series = sampleYear();
+const before = RateBilling.calculateBills(series).totals;
+
+let rates;
+if (process.argv[2]) {
+    rates = JSON.parse(fs.readFileSync(process.argv[2], 'utf8'));
+} else {
+    rates = RateBilling.exportRates();
+    const reo = rates.plans['tou-reo'];
+    reo.energy.on_peak = Number((reo.energy.on_peak * 1.1).toFixed(6));
+    reo.energy.off_peak = Number((reo.energy.off_peak * 1.1).toFixed(6));
+    rates.windows['late-peak'] = { season: 'summer', weekdays: [1, 2, 3, 4, 5], hours: [15, 20], holidays: false };
+    reo.periods = [{ period: 'on_peak', window: 'late-peak' }, { period: 'off_peak' }];
+}
+
+// loadRates throws with every problem listed if the file has a mistake in it
+RateBilling.loadRates(rates);
+const after = RateBilling.calculateBills(series).totals;
+RateBilling.resetRates();
+
+RateBilling.PLAN_IDS.forEach(id => {
+    const change = after[id].total - before[id].total;
+    console.log(`${RateBilling.RATE_PLANS[id].name.padEnd(8)} $${before[id].total.toFixed(2).padStart(9)} -> $${after[id].total.toFixed(2).padStart(9)}  (${change >= 0 ? '+' : '-'}$${Math.abs(change).toFixed(2)})`);
+});
@@ -0,0 +1,38 @@
+// Pilot tariff: define a trial TOU plan from its periods and rates alone, flag it as a pilot,
+// and see how it compares with the standard offers on the same usage. The numbers below are
+// made up; copy the pilot's rate sheet into them to study a real one.
+//
+//   node examples/pilot-tariff.js [usage.csv]
+const RateBilling = require('../web/billing.js');
+const { sampleYear, readUsageText } = require('./sample-usage.js');
+
+RateBilling.registerPlan('pilot-late-peak', {
+    info: {
+        name: 'PILOT-LP',
+        title: 'Late Peak pilot',
+        minTermMonths: 12,
+        rounding: 'line',
+        lastVerified: '2025-01-01',
+        summer: { from: '06-01', to: '09-30' },
+        pilot: { program: 'Example pilot', enrollmentCap: 1000, note: 'Closed to new customers once the cap is reached.' },
+        periods: [
+            { period: 'on_peak', window: 'summer-weekday-late-peak' },
+            { period: 'off_peak' }
+        ]
+    },
+    windows: { 'summer-weekday-late-peak': { season: 'summer', weekdays: [1, 2, 3, 4, 5], hours: [16, 20], holidays: false } },
+    energy: { on_peak: 0.32, off_peak: 0.07 },
+    service: { basicPerDay: 0.4603 }
+});
+
+const file = process.argv[2];
+const series = file ? RateBilling.parseUsageCsv(readUsageText(file)) : sampleYear();
+const { totals } = RateBilling.calculateBills(series);
+
+[...RateBilling.PLAN_IDS].sort((a, b) => totals[a].total - totals[b].total).forEach(id => {
+    const plan = RateBilling.RATE_PLANS[id];
+    const pilot = plan.pilot
+        ? `  pilot: ${plan.pilot.program}, ${plan.pilot.enrollmentCap ? `capped at ${plan.pilot.enrollmentCap} customers` : 'no published cap'}. ${plan.pilot.note || ''}`
+        : '';
+    console.log(`${plan.name.padEnd(10)} $${totals[id].total.toFixed(2).padStart(8)}${pilot}`);
+});
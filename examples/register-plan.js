@@ -0,0 +1,22 @@
+// Custom plan: register a schedule the library doesn't ship with and bill it next to the
+// built-in plans. This one is a made-up flat 12¢/kWh plan with a weekend discount.
+//
+//   node examples/register-plan.js
+const RateBilling = require('../web/billing.js');
+const { sampleYear } = require('./sample-usage.js');
+
+RateBilling.registerPlan('flat-weekend', {
+    info: { name: 'FLAT-WKND', minTermMonths: 0, rounding: 'line', summer: { from: '06-01', to: '09-30' }, lastVerified: '2025-01-01' },
+    energy: { weekday: 0.12, weekend: 0.09 },
+    service: { basicPerDay: 0.4603 },
+    classify: dt => (dt.getDay() === 0 || dt.getDay() === 6 ? 'weekend' : 'weekday'),
+    bill: (m, usage) => ({
+        weekday: (usage.periods.weekday || 0) * 0.12,
+        weekend: (usage.periods.weekend || 0) * 0.09
+    })
+});
+
+const { totals } = RateBilling.calculateBills(sampleYear());
+RateBilling.PLAN_IDS.forEach(id => {
+    console.log(`${RateBilling.RATE_PLANS[id].name.padEnd(10)} $${totals[id].total.toFixed(2)}`);
+});
@@ -0,0 +1,31 @@
+// A year of made-up hourly usage for the examples: a base load, summer air conditioning
+// in the afternoon and evening, and winter heating in the morning.
+function sampleYear(year = 2024) {
+    const series = [];
+    for (let dt = new Date(year, 0, 1); dt.getFullYear() === year; dt = new Date(dt.getFullYear(), dt.getMonth(), dt.getDate(), dt.getHours() + 1)) {
+        const hour = dt.getHours();
+        const month = dt.getMonth() + 1;
+        let kwh = 0.5;
+        if (month >= 6 && month <= 9 && hour >= 12 && hour < 22) kwh += 2.2;
+        if ((month <= 2 || month === 12) && hour >= 5 && hour < 9) kwh += 1.6;
+        series.push({ dt, kwh });
+    }
+    return series;
+}
+
+// Text of a usage file for the command-line scripts: "-" is standard input, and gzip or
+// Zstandard files (by extension or first bytes) are decompressed in memory. Zstandard needs
+// Node 22.15 or later.
+function readUsageText(file) {
+    const fs = require('fs');
+    const zlib = require('zlib');
+    const bytes = fs.readFileSync(file === '-' ? 0 : file);
+    if (/\.gz$/i.test(file) || (bytes[0] === 0x1f && bytes[1] === 0x8b)) return zlib.gunzipSync(bytes).toString('utf8');
+    if (/\.zst$/i.test(file) || (bytes.length >= 4 && bytes.readUInt32LE(0) === 0xfd2fb528)) {
+        if (!zlib.zstdDecompressSync) throw new Error(`${file}: this Node version can't read Zstandard files; use Node 22.15+ or run zstd -d first`);
+        return zlib.zstdDecompressSync(bytes).toString('utf8');
+    }
+    return bytes.toString('utf8');
+}
+
+module.exports = { sampleYear, readUsageText };
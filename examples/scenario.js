@@ -0,0 +1,38 @@
+// Scenario run: add nightly EV charging to a year of usage and see whether the best plan
+// changes, checking each plan's 12-month commitment against R-30 like the page does.
+//
+//   node examples/scenario.js [kWh per night] [start hour]
+const RateBilling = require('../web/billing.js');
+const { sampleYear } = require('./sample-usage.js');
+
+const nightlyKwh = Number(process.argv[2] || 10);
+const startHour = Number(process.argv[3] || 23);
+const chargerKw = 7.2;
+
+// Charge at full power from startHour until the night's kWh are in
+function withEv(series) {
+    return series.map(({ dt, kwh }) => {
+        const hoursIn = (dt.getHours() - startHour + 24) % 24;
+        const charge = Math.max(0, Math.min(chargerKw, nightlyKwh - hoursIn * chargerKw));
+        return { dt, kwh: kwh + charge };
+    });
+}
+
+function bestPlan(series) {
+    const { monthlyBills, totals } = RateBilling.calculateBills(series);
+    const eligible = RateBilling.PLAN_IDS
+        .filter(id => RateBilling.checkTermCommitment(id, monthlyBills).eligible)
+        .sort((a, b) => totals[a].total - totals[b].total);
+    return { planId: eligible[0], totals };
+}
+
+const scenarios = [
+    { label: 'As used', series: sampleYear() },
+    { label: `With ${nightlyKwh} kWh EV from ${startHour}:00`, series: withEv(sampleYear()) }
+];
+
+scenarios.forEach(({ label, series }) => {
+    const { planId, totals } = bestPlan(series);
+    const summary = RateBilling.PLAN_IDS.map(id => `${RateBilling.RATE_PLANS[id].name} $${totals[id].total.toFixed(2)}`).join(', ');
+    console.log(`${label}: best ${RateBilling.RATE_PLANS[planId].name}\n  ${summary}`);
+});
@@ -0,0 +1,23 @@
+// Schedule only: save a plan's TOU schedule as JSON, then look up periods and rates with nothing
+// but web/schedule.js, the way a thermostat or charger script would without the billing code.
+//
+//   node examples/schedule-only.js [plan] [YYYY-MM-DD]
+const RateBilling = require('../web/billing.js');
+const RateSchedule = require('../web/schedule.js');
+
+const planId = RateBilling.findPlanId(process.argv[2] || 'tou-reo');
+if (!planId) {
+    console.error(`Unknown plan ${process.argv[2]}`);
+    process.exit(1);
+}
+const [y, m, d] = (process.argv[3] || '2024-07-01').split('-').map(Number);
+
+// This JSON is all a device needs to carry; write it to a file once and ship it with schedule.js
+const json = JSON.stringify(RateBilling.scheduleFor(planId));
+const schedule = JSON.parse(json);
+
+console.log(`${RateBilling.RATE_PLANS[planId].name} on ${new Date(y, m - 1, d).toDateString()} (${json.length} bytes of schedule):`);
+for (let hour = 0; hour < 24; hour++) {
+    const { period, rate } = RateSchedule.rateAt(schedule, new Date(y, m - 1, d, hour));
+    console.log(`  ${String(hour).padStart(2, '0')}:00  ${period.padEnd(14)} ${(rate * 100).toFixed(2)} cents/kWh`);
+}
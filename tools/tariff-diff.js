@@ -0,0 +1,62 @@
+// Compare two rates files (the layout Analysis Options > Rates downloads) rate by rate,
+// then price a year of usage under both to show what the change does to the bill.
+//
+//   node tools/tariff-diff.js old.json new.json [usage.csv]
+//
+// Pass "builtin" for either file to use the rates the page ships with. The usage CSV is the
+// same timestamp,kWh layout examples/bill-from-csv.js reads, or "-" for standard input; without
+// one a sample year is used.
+const fs = require('fs');
+const path = require('path');
+const RateBilling = require('../web/billing.js');
+const { sampleYear, readUsageText } = require('../examples/sample-usage.js');
+
+const [oldFile, newFile, usageFile] = process.argv.slice(2);
+if (!oldFile || !newFile) {
+    console.error('Usage: node tools/tariff-diff.js old.json new.json [usage.csv]');
+    process.exit(1);
+}
+
+function readRates(file) {
+    return file === 'builtin' ? RateBilling.defaultRates() : JSON.parse(fs.readFileSync(file, 'utf8'));
+}
+
+function formatValue(value) {
+    if (value === null) return '(none)';
+    return typeof value === 'object' ? JSON.stringify(value) : String(value);
+}
+
+const signed = (value, digits) => `${value >= 0 ? '+' : '-'}${Math.abs(value).toFixed(digits)}`;
+
+let oldRates, newRates, changes;
+try {
+    oldRates = readRates(oldFile);
+    newRates = readRates(newFile);
+    changes = RateBilling.diffRates(oldRates, newRates);
+} catch (e) {
+    console.error(e.message);
+    process.exit(1);
+}
+
+console.log(`Rate changes, ${path.basename(oldFile)} -> ${path.basename(newFile)}:`);
+if (changes.length === 0) console.log('  No differences');
+const width = Math.max(0, ...changes.map(change => change.what.length));
+changes.forEach(({ what, from, to, changePct }) => {
+    const pct = changePct === null ? '' : `  (${signed(changePct, 1)}%)`;
+    console.log(`  ${what.padEnd(width)}  ${formatValue(from)} -> ${formatValue(to)}${pct}`);
+});
+
+const series = usageFile ? RateBilling.parseUsageCsv(readUsageText(usageFile)) : sampleYear();
+if (series.length === 0) {
+    console.error(`No hourly readings found in ${usageFile === '-' ? 'standard input' : usageFile}`);
+    process.exit(1);
+}
+const before = RateBilling.withRates(oldRates, () => RateBilling.calculateBills(series).totals);
+const after = RateBilling.withRates(newRates, () => RateBilling.calculateBills(series).totals);
+
+console.log(`\nBill impact on ${usageFile === '-' ? 'standard input' : usageFile ? path.basename(usageFile) : 'a sample year'}:`);
+RateBilling.PLAN_IDS.forEach(id => {
+    const change = after[id].total - before[id].total;
+    const pct = before[id].total ? `  (${signed(change / before[id].total * 100, 1)}%)` : '';
+    console.log(`  ${RateBilling.RATE_PLANS[id].name.padEnd(8)} $${before[id].total.toFixed(2).padStart(9)} -> $${after[id].total.toFixed(2).padStart(9)}  ${signed(change, 2).replace(/^([+-])/, '$1$')}${pct}`);
+});
@@ -0,0 +1,1974 @@
+// Georgia Power residential billing: tariff data, TOU classification and the per-plan bill math.
+// No DOM access, so it runs the same in the page (as window.RateBilling, loaded after schedule.js
+// and before script.js) and in Node (const RateBilling = require('./billing.js')).
+//
+// Shapes used by the public functions:
+//   usage series   - array of { dt: Date (local wall clock), kwh: number }, one per reading, sorted by dt
+//   monthly usage  - aggregateMonthly() buckets, keyed "YYYY-MM"
+//   bill           - { total, breakdown: { line key: amount } }, see finalizeBill
+//   rate plan      - RATE_PLANS entry (name, schedule, term, rounding, season) plus its ENERGY_RATES
+//
+// Example:
+//   const { totals } = RateBilling.calculateBills(series);
+//   totals['tou-reo'].total  // dollars over the whole series
+(function (root) {
+    'use strict';
+
+    // Period and rate lookups for a single moment live in schedule.js so they can be used alone
+    const RateSchedule = typeof module !== 'undefined' && module.exports ? require('./schedule.js') : root.RateSchedule;
+    const { inSeason } = RateSchedule;
+
+    // Constants for Riders & Taxes. The rate tables below are objects so that
+    // loadRates() can update them in place from a rates file.
+    const FUEL_RATES = {
+        summer: 0.045876, // ~4.6 cents/kWh (Jun-Sep)
+        winter: 0.042859 // ~4.3 cents/kWh (Oct-May)
+    };
+
+    // Seasons are inclusive "MM-DD" day ranges; the fuel rider and each tariff carry their own
+    const FCR_SEASON = { from: '06-01', to: '09-30' };
+
+    // Percentage riders, fees and taxes on the pre-tax bill (base charges + fuel), one entry per
+    // bill line. The tariff sheets in reference/ name the ECCR, DSM Residential and Municipal
+    // Franchise Fee schedules but not their factors, so until those are sourced they are billed
+    // together at the ~12% seen on actual bills, sales tax included, rather than as made-up lines.
+    const FEE_LINES = [
+        {
+            key: 'fees', label: 'Riders, Fees & Taxes', rate: 0.12,
+            title: 'Environmental Compliance Cost Recovery, Demand Side Management Residential and Municipal Franchise Fee riders plus sales tax, about 12% on actual bills'
+        }
+    ];
+    const TAX_RATE = 1 + FEE_LINES.reduce((sum, f) => sum + f.rate, 0);
+
+    // Basic service charge ($/day) and, for plans that bill one, the demand charge: $/kW of the
+    // month's highest average kW over demandWindowMinutes (DETERMINATION OF BILLING DEMAND)
+    const SERVICE_RATES = {
+        'tou-reo': { basicPerDay: 0.4603 },
+        'tou-oa': { basicPerDay: 0.4603 },
+        'tou-rd': { basicPerDay: 0.4603, demandPerKw: 12.21, demandWindowMinutes: 60 },
+        'r30': { basicPerDay: 0.4603 }
+    };
+
+    // Energy rates ($/kWh) per plan and period
+    const ENERGY_RATES = {
+        'tou-reo': { on_peak: 0.297868, off_peak: 0.076281 },
+        'tou-oa': { on_peak: 0.297868, off_peak: 0.101676, super_off_peak: 0.021859 },
+        'tou-rd': { on_peak: 0.142986, off_peak: 0.015288 },
+        // Block rates per season: limits are the monthly kWh where each next rate starts.
+        // A season may add credit: { upTo, rate } - a base-load credit ($/kWh, positive)
+        // on its first upTo kWh, billed as a negative line. No current R-30 season has one.
+        'r30': {
+            summer: { limits: [650, 1000], rates: [0.086121, 0.143047, 0.148051] },
+            winter: { limits: [], rates: [0.080602] }
+        }
+    };
+
+    // Plan glossary shown on each card. minTermMonths is the required stay after enrolling
+    // (TERM OF CONTRACT in each tariff); R-30 can be left for another residential plan any time.
+    // rounding is how each monthly bill rounds to cents (see finalizeBill).
+    // cheapestPeriod is the classifyHour() period that shiftable load should land in.
+    // lastVerified is when the rates were last checked against the tariff sheet (YYYY-MM-DD).
+    // summer is the tariff's summer season (see inSeason); it may start or end mid-month.
+    // periods lists the plan's TOU periods in the order they are checked (see classifyHour).
+    // aliases are the names Georgia Power markets the plan under, accepted wherever a plan is named.
+    // pilot marks a trial or limited-enrollment tariff (none of the built-in plans):
+    // { program, enrollmentCap (customers, or null if not published), note }.
+    const RATE_PLANS = {
+        'tou-reo': {
+            name: 'TOU-REO',
+            title: 'Residential Energy Only',
+            aliases: ['Nights & Weekends'],
+            schedule: 'TOU-REO-18',
+            reference: 'reference/tou-reo-18.pdf',
+            description: 'Sold as Nights & Weekends. Two prices: a high on-peak price 2-7 PM on summer weekdays and a low off-peak price at all other times, including all weekend hours year-round. No demand charge.',
+            eligibility: 'Any residential customer with a separately metered home. 12-month minimum stay. Independence Day and Labor Day are off-peak.',
+            minTermMonths: 12,
+            rounding: 'line',
+            cheapestPeriod: 'off_peak',
+            lastVerified: '2025-01-01',
+            summer: { from: '06-01', to: '09-30' },
+            periods: [
+                { period: 'on_peak', window: 'summer-weekday-peak' },
+                { period: 'off_peak' }
+            ]
+        },
+        'tou-oa': {
+            name: 'TOU-OA',
+            title: 'Overnight Advantage',
+            schedule: 'TOU-OA-14',
+            reference: 'reference/tou-oa-14.pdf',
+            description: 'Adds a very cheap super off-peak price from 11 PM to 7 AM every day, on top of the summer weekday 2-7 PM on-peak. Built for EV charging and other overnight loads.',
+            eligibility: 'Any residential customer with a separately metered home. 12-month minimum stay. Independence Day and Labor Day are off-peak.',
+            minTermMonths: 12,
+            rounding: 'line',
+            cheapestPeriod: 'super_off_peak',
+            lastVerified: '2025-01-01',
+            summer: { from: '06-01', to: '09-30' },
+            periods: [
+                { period: 'on_peak', window: 'summer-weekday-peak' },
+                { period: 'super_off_peak', window: 'overnight' },
+                { period: 'off_peak' }
+            ]
+        },
+        'tou-rd': {
+            name: 'TOU-RD',
+            title: 'Residential Demand',
+            aliases: ['Smart Usage'],
+            schedule: 'TOU-RD-11',
+            reference: 'reference/tou-rd-11.pdf',
+            description: 'Sold as Smart Usage. Very low energy prices plus a monthly demand charge on your highest 60-minute kW. Rewards spreading usage out instead of running big loads at once.',
+            eligibility: 'Any residential customer with a separately metered home. 12-month minimum stay. Independence Day and Labor Day are off-peak.',
+            minTermMonths: 12,
+            rounding: 'line',
+            cheapestPeriod: 'off_peak',
+            lastVerified: '2025-01-01',
+            summer: { from: '06-01', to: '09-30' },
+            periods: [
+                { period: 'on_peak', window: 'summer-weekday-peak' },
+                { period: 'off_peak' }
+            ]
+        },
+        'r30': {
+            name: 'R-30',
+            title: 'Standard Residential',
+            schedule: 'R-30',
+            reference: 'reference/r-30.pdf',
+            description: 'The default plan. No time periods: one winter price, and in summer three blocks that get more expensive after 650 and 1,000 kWh.',
+            eligibility: 'All residential customers, including commonly metered dwellings. You can move to another residential plan at any time.',
+            minTermMonths: 0,
+            rounding: 'line',
+            cheapestPeriod: null,
+            lastVerified: '2025-01-01',
+            summer: { from: '06-01', to: '09-30' },
+            periods: [{ period: 'flat' }]
+        }
+    };
+    const PLAN_IDS = Object.keys(RATE_PLANS);
+
+    // --- TOU classification ---
+
+    // Named time windows that plan periods refer to, in the RateSchedule.inWindow() layout:
+    // season ('summer' is the plan's own), months, weekdays, [start, end) hours, holidays: false
+    const TOU_WINDOWS = {
+        'summer-weekday-peak': { season: 'summer', weekdays: [1, 2, 3, 4, 5], hours: [14, 19], holidays: false },
+        'overnight': { hours: [23, 7] }
+    };
+
+    // Windows added by registerPlan(), kept through resetRates()
+    const REGISTERED_WINDOWS = {};
+
+    // Holidays the TOU schedules price off-peak all day. A fixed-date holiday on a Saturday is
+    // observed the Friday before and one on a Sunday the Monday after; nth/weekday rules name
+    // the nth weekday of the month (Labor Day is the first Monday in September).
+    const HOLIDAY_RULES = [
+        { name: 'Independence Day', month: 7, day: 4 },
+        { name: 'Labor Day', month: 9, weekday: 1, nth: 1 }
+    ];
+    const HOLIDAY_YEARS = [2000, 2050];
+
+    // [{ date: 'YYYY-MM-DD', name }] for the holidays observed in a year
+    function observedHolidays(year) {
+        return HOLIDAY_RULES.map(rule => {
+            const dt = new Date(year, rule.month - 1, rule.day || 1);
+            if (rule.nth) {
+                dt.setDate(1 + ((rule.weekday - dt.getDay() + 7) % 7) + (rule.nth - 1) * 7);
+            } else if (dt.getDay() === 6) {
+                dt.setDate(dt.getDate() - 1);
+            } else if (dt.getDay() === 0) {
+                dt.setDate(dt.getDate() + 1);
+            }
+            const date = `${year}-${String(dt.getMonth() + 1).padStart(2, '0')}-${String(dt.getDate()).padStart(2, '0')}`;
+            return { date, name: rule.name };
+        });
+    }
+
+    function builtInHolidays() {
+        const dates = [];
+        for (let year = HOLIDAY_YEARS[0]; year <= HOLIDAY_YEARS[1]; year++) {
+            dates.push(...observedHolidays(year).map(h => h.date));
+        }
+        return dates;
+    }
+
+    // 'YYYY-MM-DD' dates that windows with holidays: false skip: the observed HOLIDAY_RULES
+    // dates over HOLIDAY_YEARS, with a rates file's additions and removals
+    const HOLIDAYS = builtInHolidays();
+
+    function inWindow(window, planId, dt) {
+        return RateSchedule.inWindow(window, dt, { summer: RATE_PLANS[planId].summer, holidays: HOLIDAYS });
+    }
+
+    // A plan's TOU schedule in the layout schedule.js evaluates. It shares the live rate tables,
+    // so it follows loadRates(); JSON.stringify it to keep a copy for a device or script.
+    function scheduleFor(planId) {
+        return {
+            summer: RATE_PLANS[planId].summer,
+            windows: TOU_WINDOWS,
+            holidays: HOLIDAYS,
+            periods: RATE_PLANS[planId].periods,
+            energy: ENERGY_RATES[planId]
+        };
+    }
+
+    // Period from a plan's declared periods: the first whose window matches, falling
+    // through to the last (which has none). R-30's only period is 'flat'.
+    function classifyByPeriods(planId, dt) {
+        return RateSchedule.periodAt(scheduleFor(planId), dt);
+    }
+
+    // TOU period for a plan at a given hour
+    function classifyHour(planId, dt) {
+        return PLAN_MODELS[planId].classify(dt);
+    }
+
+    function isOnPeak(dt, planId = 'tou-reo') {
+        return classifyHour(planId, dt) === 'on_peak';
+    }
+
+    function getTouOaPeriod(dt) {
+        return classifyHour('tou-oa', dt);
+    }
+
+    // --- Monthly bills ---
+
+    // "YYYY-MM" -> { total, days: Set(dayStr), fcr,
+    //              plans: { planId: { periods: { period: kWh }, summerKwh, summerDays: Set(dayStr), demandKw } } }
+    // where periods come from each plan's classify() and summer is the plan's own season.
+    // demandKw is the billing demand for plans with a demand window (0 for the rest): the highest
+    // kWh in any one window, over the window's hours. Windows are fixed blocks on the clock
+    // (every hour on the hour for 60 minutes), so 15- and 30-minute readings add up to the
+    // tariff's measurement; a reading longer than the window counts as its own average kW.
+    function aggregateMonthly(records) {
+        const monthlyUsage = {};
+        const minutes = readingMinutes(records);
+        const demandPlans = PLAN_IDS.filter(id => SERVICE_RATES[id].demandWindowMinutes);
+        const windowKwh = {}; // "planId slot" -> { monthKey, kwh }
+
+        records.forEach((r, i) => {
+            const dt = r.dt;
+            const kwh = r.kwh;
+            const month = dt.getMonth() + 1; // 1-12
+            const monthKey = `${dt.getFullYear()}-${String(month).padStart(2, '0')}`;
+            const dayKey = `${monthKey}-${String(dt.getDate()).padStart(2, '0')}`;
+
+            // Initialize monthly bucket
+            if (!monthlyUsage[monthKey]) {
+                const plans = {};
+                PLAN_IDS.forEach(id => {
+                    plans[id] = { periods: {}, summerKwh: 0, summerDays: new Set(), demandKw: 0 };
+                });
+                monthlyUsage[monthKey] = { total: 0, days: new Set(), fcr: 0, plans };
+            }
+            const m = monthlyUsage[monthKey];
+            m.total += kwh;
+            m.days.add(dayKey);
+
+            // Both passes through the hour clocks repeat in November have the same clock times,
+            // so the second pass (marked repeated) gets windows of its own
+            demandPlans.forEach(id => {
+                const windowMins = SERVICE_RATES[id].demandWindowMinutes;
+                if (minutes[i] > windowMins) {
+                    const usage = m.plans[id];
+                    usage.demandKw = Math.max(usage.demandKw, kwh / (minutes[i] / 60));
+                    return;
+                }
+                const slot = `${id} ${Math.floor(dt.getTime() / (windowMins * 60000))}${r.repeated ? ' repeated' : ''}`;
+                if (!windowKwh[slot]) windowKwh[slot] = { monthKey, kwh: 0 };
+                windowKwh[slot].kwh += kwh;
+            });
+
+            // FCR Calculation
+            m.fcr += kwh * (FUEL_RATES[inSeason(FCR_SEASON, dt) ? 'summer' : 'winter']);
+
+            // Classify
+            PLAN_IDS.forEach(id => {
+                const usage = m.plans[id];
+                const period = PLAN_MODELS[id].classify(dt);
+                usage.periods[period] = (usage.periods[period] || 0) + kwh;
+                if (inSeason(RATE_PLANS[id].summer, dt)) {
+                    usage.summerKwh += kwh;
+                    usage.summerDays.add(dayKey);
+                }
+            });
+        });
+
+        Object.entries(windowKwh).forEach(([slot, { monthKey, kwh }]) => {
+            const id = slot.split(' ')[0];
+            const usage = monthlyUsage[monthKey].plans[id];
+            usage.demandKw = Math.max(usage.demandKw, kwh / (SERVICE_RATES[id].demandWindowMinutes / 60));
+        });
+
+        return monthlyUsage;
+    }
+
+    // Riders and credits that come and go by PSC order, one per line:
+    //   "2024-06..2024-12 -0.0025/kWh Fuel refund" - per-kWh adder (negative for a credit), taxed like energy
+    //   "2024-09 -25 Storm credit"                 - one-time amount on that month's bill, after taxes
+    function parseBillAdjustments(text) {
+        const items = [];
+        const errors = [];
+        text.split('\n').map(line => line.trim()).filter(Boolean).forEach(line => {
+            const match = line.match(/^(\d{4}-\d{2})(?:\.\.(\d{4}-\d{2}))?\s+([+-]?\d*\.?\d+)(\/kwh)?\s*(.*)$/i);
+            if (!match) {
+                errors.push(line);
+                return;
+            }
+            items.push({
+                from: match[1],
+                to: match[2] || match[1],
+                amount: parseFloat(match[3]),
+                perKwh: Boolean(match[4]),
+                label: match[5] || (match[4] ? 'Rider adjustment' : 'Bill credit')
+            });
+        });
+        return { items, errors };
+    }
+
+    // Per-kWh rate and one-time amount from the adjustments active in a "YYYY-MM" month
+    function adjustmentsFor(adjustments, key) {
+        const active = adjustments.filter(a => key >= a.from && key <= a.to);
+        return {
+            perKwh: active.filter(a => a.perKwh).reduce((sum, a) => sum + a.amount, 0),
+            oneTime: active.filter(a => !a.perKwh).reduce((sum, a) => sum + a.amount, 0)
+        };
+    }
+
+    // --- Costs (Base + FCR) * Tax, for one month ---
+    // adjustments are parseBillAdjustments() items; oneTime: false leaves out one-time
+    // credits, for the second part of a split month
+    function billMonth(key, m, { oneTime = true, adjustments = [] } = {}) {
+        const fixed = planId => SERVICE_RATES[planId].basicPerDay * m.days.size;
+        const fcr = m.fcr;
+        const adjustment = adjustmentsFor(adjustments, key);
+        // Adjustment lines only appear on months that have them, and may be negative
+        const riders = adjustment.perKwh !== 0 ? { kwhRiders: m.total * adjustment.perKwh } : {};
+        const credit = oneTime ? adjustment.oneTime : 0;
+
+        const bills = {};
+        PLAN_IDS.forEach(id => {
+            const lines = PLAN_MODELS[id].bill(m, m.plans[id]);
+            bills[id] = finalizeBill(id, { fixed: fixed(id), ...lines, fcr, ...riders }, credit);
+        });
+        return bills;
+    }
+
+    // R-30 block charges. A month the season boundary cuts through is billed in two parts,
+    // with each season's blocks shrunk to the share of the month's days in it.
+    function billR30Blocks(m, usage) {
+        const r30Rates = ENERGY_RATES['r30'];
+        const summerShare = usage.summerDays.size / m.days.size;
+        const summer = priceBlocks(usage.summerKwh, r30Rates.summer, summerShare);
+        const winter = priceBlocks(m.total - usage.summerKwh, r30Rates.winter, 1 - summerShare);
+        const lines = {
+            // A rates file may give summer fewer or more blocks; any past the third bill as tier 3
+            summerTier1: summer.amounts[0],
+            summerTier2: summer.amounts[1] || 0,
+            summerTier3: summer.amounts.slice(2).reduce((sum, a) => sum + a, 0),
+            winter: winter.amounts.reduce((sum, a) => sum + a, 0)
+        };
+        if (summer.credit + winter.credit > 0) {
+            lines.baseLoadCredit = -(summer.credit + winter.credit);
+        }
+        return lines;
+    }
+
+    // A plan billed per TOU period: each period's kWh at ENERGY_RATES[planId][period], as a
+    // camelCase line (on_peak -> onPeak). extraLines adds anything else, like a demand charge.
+    function touPlan(planId, extraLines = () => ({})) {
+        return {
+            classify: dt => classifyByPeriods(planId, dt),
+            bill: (m, usage) => {
+                const lines = {};
+                Object.entries(ENERGY_RATES[planId]).forEach(([period, rate]) => {
+                    lines[period.replace(/_(\w)/g, (_, c) => c.toUpperCase())] = (usage.periods[period] || 0) * rate;
+                });
+                return { ...lines, ...extraLines(m, usage) };
+            }
+        };
+    }
+
+    // How each plan classifies hours and bills a month:
+    //   classify(dt)   - the TOU period of an hour (aggregateMonthly sums kWh per period)
+    //   bill(m, usage) - the month's energy and demand lines, from the aggregateMonthly() bucket m
+    //                    and usage = m.plans[planId]; billMonth adds the basic service, fuel,
+    //                    riders and fees around them
+    const PLAN_MODELS = {
+        'tou-reo': touPlan('tou-reo'),
+        'tou-oa': touPlan('tou-oa'),
+        'tou-rd': touPlan('tou-rd', (m, usage) => ({ demand: usage.demandKw * SERVICE_RATES['tou-rd'].demandPerKw })),
+        'r30': { classify: dt => classifyByPeriods('r30', dt), bill: billR30Blocks }
+    };
+
+    // Add a plan from another program, e.g. another residential schedule or a pilot. info is its
+    // RATE_PLANS entry (at least name, minTermMonths, rounding and summer), energy and service its
+    // ENERGY_RATES and SERVICE_RATES entries, with classify and bill as in PLAN_MODELS.
+    // A TOU plan can instead declare info.periods and leave out classify and bill: it is then
+    // classified and billed like the built-in TOU plans, with a demand line if service sets
+    // demandPerKw and demandWindowMinutes. windows adds the TOU_WINDOWS its periods name.
+    // calculateBills then bills it alongside the built-in plans; the page only shows those four.
+    function registerPlan(id, { info, energy, service, windows = {}, classify, bill }) {
+        if (RATE_PLANS[id]) throw new Error(`Plan ${id} already exists`);
+        const clash = Object.keys(windows).find(name => TOU_WINDOWS[name]);
+        if (clash) throw new Error(`Window ${clash} already exists`);
+        if (!(classify && bill) && !info.periods) throw new Error(`Plan ${id} needs classify and bill, or periods`);
+        RATE_PLANS[id] = info;
+        ENERGY_RATES[id] = energy;
+        SERVICE_RATES[id] = service;
+        Object.assign(REGISTERED_WINDOWS, windows);
+        Object.assign(TOU_WINDOWS, windows);
+        PLAN_MODELS[id] = classify && bill ? { classify, bill } : touPlan(id, (m, usage) =>
+            (SERVICE_RATES[id].demandPerKw ? { demand: usage.demandKw * SERVICE_RATES[id].demandPerKw } : {}));
+        PLAN_IDS.push(id);
+    }
+
+    // Charge per block for one season's usage, plus any base-load credit (as a positive amount).
+    // share scales the block limits for a month only partly in the season.
+    function priceBlocks(usage, season, share) {
+        const bounds = [0, ...season.limits.map(limit => limit * share), Infinity];
+        const amounts = season.rates.map((rate, i) =>
+            Math.max(0, Math.min(usage, bounds[i + 1]) - bounds[i]) * rate);
+        const credit = season.credit && usage > 0
+            ? Math.min(usage, season.credit.upTo * share) * season.credit.rate
+            : 0;
+        return { amounts, credit };
+    }
+
+    // Energy and demand charges; everything else on the bill is a fixed, fuel or fee line
+    const BLOCK_KEYS = ['onPeak', 'offPeak', 'superOffPeak', 'demand', 'summerTier1', 'summerTier2', 'summerTier3', 'winter', 'baseLoadCredit'];
+
+    function roundCents(amount) {
+        return Math.round((amount + Number.EPSILON) * 100) / 100;
+    }
+
+    // Add the fee lines and apply the tariff's rounding policy to one month's bill:
+    //   'line'  - every line is rounded to the cent and the total is their sum (how the bill prints)
+    //   'block' - only energy/demand block charges are rounded, then the total
+    //   'total' - only the final total is rounded
+    //   'none'  - no rounding
+    // oneTime is a bill credit (negative) or charge added after taxes.
+    function finalizeBill(planId, charges, oneTime = 0) {
+        const policy = RATE_PLANS[planId].rounding;
+        const lines = {};
+        Object.entries(charges).forEach(([key, amount]) => {
+            const roundLine = policy === 'line' || (policy === 'block' && BLOCK_KEYS.includes(key));
+            lines[key] = roundLine ? roundCents(amount) : amount;
+        });
+
+        const base = Object.values(lines).reduce((sum, v) => sum + v, 0);
+        let tax = 0;
+        FEE_LINES.forEach(f => {
+            const fee = policy === 'line' ? roundCents(base * f.rate) : base * f.rate;
+            lines[f.key] = fee;
+            tax += fee;
+        });
+
+        const total = policy === 'none' ? base + tax + oneTime : roundCents(base + tax + oneTime);
+        return { total, breakdown: { ...lines, tax, ...(oneTime !== 0 ? { oneTime } : {}) } };
+    }
+
+    function sumBills(bills) {
+        const sum = { total: 0, breakdown: {} };
+        bills.forEach(bill => {
+            sum.total += bill.total;
+            Object.entries(bill.breakdown).forEach(([k, v]) => {
+                sum.breakdown[k] = (sum.breakdown[k] || 0) + v;
+            });
+        });
+        return sum;
+    }
+
+    // Bill each month of a usage series under every plan, then add the months up.
+    // Returns { monthlyUsage, monthlyBills: { "YYYY-MM": { planId: bill } }, totals: { planId: bill } }.
+    function calculateBills(records, { adjustments = [] } = {}) {
+        const monthlyUsage = aggregateMonthly(records);
+        const monthlyBills = {};
+        Object.keys(monthlyUsage).sort().forEach(key => {
+            monthlyBills[key] = billMonth(key, monthlyUsage[key], { adjustments });
+        });
+        const totals = {};
+        PLAN_IDS.forEach(id => {
+            totals[id] = sumBills(Object.values(monthlyBills).map(bills => bills[id]));
+        });
+        return { monthlyUsage, monthlyBills, totals };
+    }
+
+    // --- Bill walk-through ---
+    // explainBill() retells billMonth() for one plan in plain language, step by step: how the
+    // hours were sorted, how each price was applied, and what was added on top. Amounts are the
+    // bill's own lines, so the steps add up to the same total.
+
+    const DAY_NAMES = ['Sunday', 'Monday', 'Tuesday', 'Wednesday', 'Thursday', 'Friday', 'Saturday'];
+
+    // 14 -> "2 PM", 0 and 24 -> "midnight"
+    function hourName(hour) {
+        if (hour % 24 === 0) return 'midnight';
+        if (hour === 12) return 'noon';
+        return `${hour % 12} ${hour < 12 ? 'AM' : 'PM'}`;
+    }
+
+    // "on weekdays (Monday to Friday) in summer (06-01 to 09-30), 2 PM to 7 PM, except holidays"
+    function describeWindow(window, planId) {
+        const parts = [];
+        if (window.weekdays) {
+            const days = [...window.weekdays].sort();
+            const run = days.every((d, i) => i === 0 || d === days[i - 1] + 1);
+            const list = run && days.length > 2 ? `${DAY_NAMES[days[0]]} to ${DAY_NAMES[days[days.length - 1]]}` : days.map(d => DAY_NAMES[d]).join(', ');
+            parts.push(days.join() === '1,2,3,4,5' ? `on weekdays (${list})` : `on ${list}`);
+        }
+        if (window.season) {
+            const season = window.season === 'summer' ? RATE_PLANS[planId].summer : window.season;
+            parts.push(`${window.season === 'summer' ? 'in summer' : 'between'} (${season.from} to ${season.to})`);
+        }
+        if (window.months) parts.push(`in months ${window.months.join(', ')}`);
+        parts.push(window.hours ? `from ${hourName(window.hours[0])} to ${hourName(window.hours[1])}` : 'at any hour');
+        const text = parts.join(' ');
+        if (window.holidays === false) {
+            const changed = JSON.stringify(HOLIDAYS) !== JSON.stringify(builtInHolidays());
+            const names = `${HOLIDAY_RULES.map(r => r.name).join(' and ')} as observed${changed ? ', with the rates file\'s changes' : ''}`;
+            return `${text}, except holidays (${HOLIDAYS.length ? names : 'none listed'})`;
+        }
+        return text;
+    }
+
+    function periodName(period) {
+        return period.replace(/_/g, '-');
+    }
+
+    // Steps for one plan's bill in the "YYYY-MM" month key, from the aggregateMonthly() bucket m.
+    // Returns { plan, total, steps: [{ title, lines: [text], amount? }] }, where amount is the
+    // step's dollars on the bill (steps that only explain have none).
+    function explainBill(key, m, planId, { adjustments = [] } = {}) {
+        const bill = billMonth(key, m, { adjustments })[planId];
+        const b = bill.breakdown;
+        const plan = RATE_PLANS[planId];
+        const usage = m.plans[planId];
+        const days = m.days.size;
+        const kwh = v => `${v.toFixed(1)} kWh`;
+        const dollars = v => `${v < 0 ? '-' : ''}$${Math.abs(v).toFixed(2)}`;
+        const cents = rate => `${(rate * 100).toFixed(2)}¢`;
+        const steps = [];
+
+        steps.push({
+            title: 'Usage in the month',
+            lines: [`${key} has ${kwh(m.total)} over ${days} day(s) with readings, billed on ${plan.name} (${plan.title}, schedule ${plan.schedule || 'not published'}).`]
+        });
+
+        if (planId === 'r30') {
+            const summerShare = usage.summerDays.size / days;
+            const lines = [
+                `R-30 has no time periods; the price depends on how many kWh the month uses and on the season.`,
+                `${usage.summerDays.size} of the ${days} day(s) fall in summer (${plan.summer.from} to ${plan.summer.to}), so ${kwh(usage.summerKwh)} is priced with summer blocks and ${kwh(m.total - usage.summerKwh)} with winter ones.`
+            ];
+            if (summerShare > 0 && summerShare < 1) {
+                lines.push(`Because the season changes mid-month, each season's block sizes shrink to its share of the days (${Math.round(summerShare * 100)}% summer).`);
+            }
+            steps.push({ title: 'Split the month by season', lines });
+
+            const energyLines = [];
+            [['summer', usage.summerKwh, summerShare], ['winter', m.total - usage.summerKwh, 1 - summerShare]].forEach(([name, seasonKwh, share]) => {
+                if (seasonKwh <= 0) return;
+                const season = ENERGY_RATES['r30'][name];
+                const bounds = [0, ...season.limits.map(limit => limit * share), Infinity];
+                season.rates.forEach((rate, i) => {
+                    const filled = Math.max(0, Math.min(seasonKwh, bounds[i + 1]) - bounds[i]);
+                    if (filled <= 0) return;
+                    const range = bounds[i + 1] === Infinity
+                        ? (i === 0 ? 'every kWh' : `every kWh past ${Math.round(bounds[i])}`)
+                        : `kWh ${Math.round(bounds[i])} to ${Math.round(bounds[i + 1])}`;
+                    energyLines.push(`${name[0].toUpperCase()}${name.slice(1)} block ${i + 1} (${range}): ${kwh(filled)} at ${cents(rate)} = ${dollars(filled * rate)}`);
+                });
+            });
+            if (b.baseLoadCredit) energyLines.push(`Base-load credit: ${dollars(b.baseLoadCredit)}`);
+            const energy = ['summerTier1', 'summerTier2', 'summerTier3', 'winter', 'baseLoadCredit'].reduce((sum, k) => sum + (b[k] || 0), 0);
+            steps.push({ title: 'Fill the blocks', lines: energyLines, amount: energy });
+        } else if (plan.periods) {
+            const label = period => `${periodName(period)[0].toUpperCase()}${periodName(period).slice(1)}`;
+            const rules = plan.periods.map((p, i) => (i === plan.periods.length - 1
+                ? `${label(p.period)}: every other hour.`
+                : `${label(p.period)}: ${describeWindow(TOU_WINDOWS[p.window], planId)}${i > 0 ? ', unless an earlier rule already took the hour' : ''}.`));
+            steps.push({ title: 'Sort each hour into a price period', lines: rules });
+
+            const energyLines = [];
+            let energy = 0;
+            Object.entries(ENERGY_RATES[planId]).forEach(([period, rate]) => {
+                const line = b[period.replace(/_(\w)/g, (_, c) => c.toUpperCase())] || 0;
+                energy += line;
+                energyLines.push(`${periodName(period)}: ${kwh(usage.periods[period] || 0)} at ${cents(rate)} = ${dollars(line)}`);
+            });
+            steps.push({ title: 'Price each period', lines: energyLines, amount: energy });
+        }
+
+        if (b.demand !== undefined) {
+            const service = SERVICE_RATES[planId];
+            steps.push({
+                title: 'Add the demand charge',
+                lines: [
+                    `Billing demand is the month's highest average kW over any ${service.demandWindowMinutes}-minute block on the clock: ${usage.demandKw.toFixed(2)} kW here.`,
+                    `${usage.demandKw.toFixed(2)} kW at $${service.demandPerKw.toFixed(2)}/kW = ${dollars(b.demand)}`
+                ],
+                amount: b.demand
+            });
+        }
+
+        const known = new Set(['fixed', 'fcr', 'kwhRiders', 'tax', 'oneTime', 'demand', 'summerTier1', 'summerTier2', 'summerTier3', 'winter', 'baseLoadCredit',
+            ...FEE_LINES.map(f => f.key), ...Object.keys(ENERGY_RATES[planId]).map(p => p.replace(/_(\w)/g, (_, c) => c.toUpperCase()))]);
+        const other = Object.keys(b).filter(k => !known.has(k));
+        if (other.length > 0) {
+            steps.push({ title: 'Other plan charges', lines: other.map(k => `${k}: ${dollars(b[k])}`), amount: other.reduce((sum, k) => sum + b[k], 0) });
+        }
+
+        steps.push({
+            title: 'Add the basic service charge',
+            lines: [`${days} day(s) at $${SERVICE_RATES[planId].basicPerDay.toFixed(4)}/day = ${dollars(b.fixed)}, whatever the usage.`],
+            amount: b.fixed
+        });
+
+        const fuelRate = m.total > 0 ? m.fcr / m.total : 0;
+        steps.push({
+            title: 'Add fuel cost recovery',
+            lines: [`Every kWh also pays for fuel, whatever the plan: ${kwh(m.total)} at ${cents(fuelRate)}${Math.abs(fuelRate - FUEL_RATES.summer) > 1e-9 && Math.abs(fuelRate - FUEL_RATES.winter) > 1e-9 ? ' on average (the fuel season changes mid-month)' : ''} = ${dollars(b.fcr)}`],
+            amount: b.fcr
+        });
+
+        if (b.kwhRiders) {
+            const active = adjustments.filter(a => a.perKwh && key >= a.from && key <= a.to);
+            steps.push({
+                title: 'Apply riders in effect this month',
+                lines: active.map(a => `${a.label}: ${kwh(m.total)} at ${cents(a.amount)} = ${dollars(m.total * a.amount)}`),
+                amount: b.kwhRiders
+            });
+        }
+
+        const base = Object.keys(b).filter(k => !['tax', 'oneTime', ...FEE_LINES.map(f => f.key)].includes(k)).reduce((sum, k) => sum + b[k], 0);
+        steps.push({
+            title: 'Add percentage riders, fees and taxes',
+            lines: [
+                `These are percentages of everything so far (${dollars(base)}):`,
+                ...FEE_LINES.map(f => `${f.title}: ${(f.rate * 100).toFixed(1)}% = ${dollars(b[f.key])}`)
+            ],
+            amount: b.tax
+        });
+
+        if (b.oneTime) {
+            steps.push({
+                title: 'Apply one-time credits and charges',
+                lines: adjustments.filter(a => !a.perKwh && key >= a.from && key <= a.to).map(a => `${a.label}: ${dollars(a.amount)}, after taxes`),
+                amount: b.oneTime
+            });
+        }
+
+        const rounding = {
+            line: 'Each line is rounded to the cent, and the total is their sum.',
+            block: 'Energy and demand lines are rounded to the cent, then the total.',
+            total: 'Only the total is rounded to the cent.',
+            none: 'Nothing is rounded.'
+        }[plan.rounding];
+        steps.push({ title: 'Total', lines: [`${dollars(bill.total)} for the month. ${rounding}`], amount: bill.total });
+
+        return { plan: planId, total: bill.total, steps };
+    }
+
+    // --- Recommendation drivers ---
+    // costDrivers() splits the difference between two plans' totals into the parts of the usage
+    // behind it: the kWh in each of a TOU plan's periods priced against the other plan's
+    // average energy rate, the demand charge and the basic service charge, each with fees.
+    // The parts add up to the difference, apart from fuel and riders, which every plan pays
+    // alike, and rounding.
+
+    const DRIVER_COUNT = 3;
+
+    // "2024-07" -> "July 2024"
+    function monthName(key) {
+        const [year, month] = key.split('-').map(Number);
+        return new Date(year, month - 1, 1).toLocaleString('en-US', { month: 'long', year: 'numeric' });
+    }
+
+    // The DRIVER_COUNT largest reasons planId costs more or less than otherId, from
+    // calculateBills() output. Returns [{ text, amount }], biggest first, where amount is the
+    // dollars the driver adds to planId's total against otherId's (negative when it saves).
+    function costDrivers({ monthlyUsage, totals }, planId, otherId) {
+        if (!otherId || planId === otherId) return [];
+        const months = Object.keys(monthlyUsage);
+        const totalKwh = months.reduce((sum, key) => sum + monthlyUsage[key].total, 0);
+        if (totalKwh <= 0) return [];
+        const isTou = id => Object.values(ENERGY_RATES[id]).every(rate => typeof rate === 'number');
+        const energyOf = id => BLOCK_KEYS.filter(k => k !== 'demand').reduce((sum, k) => sum + (totals[id].breakdown[k] || 0), 0);
+        const name = id => RATE_PLANS[id].name;
+        const cents = rate => `${(rate * 100).toFixed(1)}¢`;
+        const drivers = [];
+
+        // Energy, period by period on the TOU plan of the two (the recommended one if both are)
+        const ref = isTou(planId) ? planId : isTou(otherId) ? otherId : null;
+        if (ref) {
+            const avgId = ref === planId ? otherId : planId;
+            const average = energyOf(avgId) / totalKwh;
+            Object.entries(ENERGY_RATES[ref]).forEach(([period, rate]) => {
+                const kwh = months.reduce((sum, key) => sum + (monthlyUsage[key].plans[ref].periods[period] || 0), 0);
+                if (kwh <= 0) return;
+                const gap = (rate - average) * kwh * TAX_RATE;
+                drivers.push({
+                    text: `${Math.round(kwh / totalKwh * 100)}% of your usage (${Math.round(kwh).toLocaleString('en-US')} kWh) is ${periodName(period)} on ${name(ref)}, ` +
+                        `at ${cents(rate)} a kWh before fuel where ${name(avgId)} averages ${cents(average)}`,
+                    amount: ref === planId ? gap : -gap
+                });
+            });
+        } else {
+            drivers.push({
+                text: `${name(planId)} averages ${cents(energyOf(planId) / totalKwh)} a kWh before fuel where ${name(otherId)} averages ${cents(energyOf(otherId) / totalKwh)}`,
+                amount: (energyOf(planId) - energyOf(otherId)) * TAX_RATE
+            });
+        }
+
+        // Demand, told from the plan that charges it (the recommended one if both do) and the
+        // month it peaked
+        const demandId = [planId, otherId].find(id => totals[id].breakdown.demand);
+        if (demandId) {
+            const peak = months.reduce((a, b) => (monthlyUsage[b].plans[demandId].demandKw > monthlyUsage[a].plans[demandId].demandKw ? b : a));
+            const share = totals[demandId].breakdown.demand / totals[demandId].total * 100;
+            drivers.push({
+                text: `demand charges are ${Math.round(share)}% of ${name(demandId)}'s total, ` +
+                    `with your peak of ${monthlyUsage[peak].plans[demandId].demandKw.toFixed(1)} kW in ${monthName(peak)}`,
+                amount: ((totals[planId].breakdown.demand || 0) - (totals[otherId].breakdown.demand || 0)) * TAX_RATE
+            });
+        }
+
+        const fixed = (totals[planId].breakdown.fixed || 0) - (totals[otherId].breakdown.fixed || 0);
+        if (Math.abs(fixed) >= 0.005) {
+            drivers.push({
+                text: `${name(planId)}'s basic service charge is $${SERVICE_RATES[planId].basicPerDay.toFixed(4)}/day, ${name(otherId)}'s $${SERVICE_RATES[otherId].basicPerDay.toFixed(4)}/day`,
+                amount: fixed * TAX_RATE
+            });
+        }
+
+        return drivers
+            .filter(d => Math.abs(d.amount) >= 0.5)
+            .sort((a, b) => Math.abs(b.amount) - Math.abs(a.amount))
+            .slice(0, DRIVER_COUNT);
+    }
+
+    // --- Plan history and commitments ---
+
+    // Plan id for a name a person might type: the id, name, schedule (with its revision number),
+    // title or a marketing alias, ignoring case, spacing and punctuation ("nights-and-weekends"
+    // and "TOU-REO-18" both find TOU-REO)
+    function findPlanId(name) {
+        const normalize = text => text.toLowerCase().replace(/&/g, 'and').replace(/[^a-z0-9]/g, '');
+        const wanted = normalize(name);
+        if (!wanted) return null;
+        return PLAN_IDS.find(id => {
+            const plan = RATE_PLANS[id];
+            return [id, plan.name, plan.schedule || '', plan.title || '', ...(plan.aliases || [])].some(n => normalize(n) === wanted);
+        }) || null;
+    }
+
+    // "YYYY-MM-DD plan" lines, each the date the household moved onto that plan
+    function parseEnrollmentHistory(text) {
+        const periods = [];
+        const errors = [];
+        text.split('\n').map(line => line.trim()).filter(Boolean).forEach(line => {
+            const match = line.match(/^(\d{4})-(\d{2})-(\d{2})\s+(.+)$/);
+            const planId = match ? findPlanId(match[4]) : null;
+            if (!match || !planId) {
+                errors.push(line);
+                return;
+            }
+            periods.push({ start: new Date(Number(match[1]), Number(match[2]) - 1, Number(match[3])), planId });
+        });
+        periods.sort((a, b) => a.start - b.start);
+        return { periods, errors };
+    }
+
+    // Plan in effect on a date; before the first entry the first listed plan is assumed
+    function planOnDate(periods, dt) {
+        let current = periods[0];
+        periods.forEach(p => { if (p.start <= dt) current = p; });
+        return current.planId;
+    }
+
+    // What the household actually paid. Every reading is billed on the plan in effect
+    // at its timestamp, so a switch month becomes two partial bills: energy splits at
+    // the switch and the daily basic service charge follows the days on each plan.
+    function billEnrollmentHistory(records, history, { adjustments = [] } = {}) {
+        if (history.periods.length === 0) return { total: 0, segments: [], splitMonths: [], errors: history.errors };
+        const byPlan = {};
+        records.forEach(r => {
+            const planId = planOnDate(history.periods, r.dt);
+            (byPlan[planId] = byPlan[planId] || []).push(r);
+        });
+
+        const segments = [];
+        Object.entries(byPlan).forEach(([planId, planRecords]) => {
+            const monthly = aggregateMonthly(planRecords);
+            Object.keys(monthly).forEach(key => {
+                segments.push({ key, planId, usage: monthly[key], firstDay: [...monthly[key].days].sort()[0] });
+            });
+        });
+        segments.sort((a, b) => a.firstDay.localeCompare(b.firstDay));
+        // A one-time credit lands once, on the first part of a split month
+        segments.forEach((seg, i) => {
+            const oneTime = i === 0 || segments[i - 1].key !== seg.key;
+            seg.total = billMonth(seg.key, seg.usage, { oneTime, adjustments })[seg.planId].total;
+            delete seg.usage;
+        });
+
+        const keys = segments.map(s => s.key);
+        const splitMonths = [...new Set(keys.filter((k, i) => keys.indexOf(k) !== i))];
+        const total = roundCents(segments.reduce((sum, s) => sum + s.total, 0));
+        return { total, segments, splitMonths, errors: history.errors };
+    }
+
+    // A switch is only worth recommending if it beats R-30 over every stretch of calendar
+    // months as long as the plan's required term. Months are counted from the "YYYY-MM" keys,
+    // so a month missing from the data shortens the stretch rather than being skipped over.
+    // Data spanning fewer months than the term is checked as one stretch; a plan that beats
+    // R-30 over it stays eligible but is marked unverified, with the months checked and a reason.
+    function checkTermCommitment(planId, monthlyBills) {
+        const term = RATE_PLANS[planId].minTermMonths;
+        if (term === 0) return { eligible: true };
+
+        const keys = Object.keys(monthlyBills).sort();
+        const monthIndex = key => Number(key.slice(0, 4)) * 12 + Number(key.slice(5, 7)) - 1;
+        const keyOf = index => `${Math.floor(index / 12)}-${String(index % 12 + 1).padStart(2, '0')}`;
+        const span = keys.length === 0 ? 0 : monthIndex(keys[keys.length - 1]) - monthIndex(keys[0]) + 1;
+        if (span < term) {
+            const savings = keys.reduce((sum, k) => sum + monthlyBills[k]['r30'].total - monthlyBills[k][planId].total, 0);
+            if (keys.length > 0 && savings <= 0) {
+                return { eligible: false, reason: `it would cost $${(-savings).toFixed(2)} more than R-30 over the ${span} month(s) of data` };
+            }
+            return {
+                eligible: true,
+                unverified: true,
+                months: span,
+                reason: `it needs a ${term}-month commitment and only ${span} month(s) of data are available, so the rest of the term is not checked`
+            };
+        }
+
+        for (let i = 0; i < keys.length && monthIndex(keys[i]) + term - 1 <= monthIndex(keys[keys.length - 1]); i++) {
+            const last = monthIndex(keys[i]) + term - 1;
+            const window = keys.filter(k => monthIndex(k) >= monthIndex(keys[i]) && monthIndex(k) <= last);
+            const savings = window.reduce((sum, k) => sum + monthlyBills[k]['r30'].total - monthlyBills[k][planId].total, 0);
+            if (savings <= 0) {
+                return { eligible: false, reason: `it would cost $${(-savings).toFixed(2)} more than R-30 over the ${term} months ${keys[i]} to ${keyOf(last)}` };
+            }
+        }
+        return { eligible: true };
+    }
+
+    // --- Data checks ---
+
+    // Above this a "demand" reading is almost certainly a unit or interval mix-up for a home
+    const MAX_PLAUSIBLE_KW = 40;
+
+    // Demand charges need readings no longer than the plan's demand window, and an implausible
+    // peak points at a unit mix-up. Returns { planId: reason } for plans that should not be compared.
+    function checkDemandAssumptions(records, monthlyUsage) {
+        const suppressed = {};
+        const intervalMins = detectIntervalMinutes(records);
+        PLAN_IDS.filter(id => SERVICE_RATES[id].demandWindowMinutes).forEach(id => {
+            const reasons = [];
+            const windowMins = SERVICE_RATES[id].demandWindowMinutes;
+            if (intervalMins && intervalMins - windowMins > 1) {
+                reasons.push(`readings are ${intervalMins} minutes apart, but the demand charge is measured over ${windowMins} minutes`);
+            }
+
+            const implausible = Object.keys(monthlyUsage).filter(key => monthlyUsage[key].plans[id].demandKw > MAX_PLAUSIBLE_KW);
+            if (implausible.length > 0) {
+                const peak = Math.max(...implausible.map(key => monthlyUsage[key].plans[id].demandKw));
+                reasons.push(`peak demand of ${peak.toFixed(1)} kW in ${implausible.join(', ')} is above the ${MAX_PLAUSIBLE_KW} kW expected for a home (check that the file is in kWh)`);
+            }
+
+            if (reasons.length > 0) suppressed[id] = reasons.join('; ');
+        });
+        return suppressed;
+    }
+
+    // Summer on-peak prices make most of the difference between plans, so a ranking built on
+    // little or no summer usage mostly reflects winter and can point the wrong way
+    const MIN_SUMMER_DAYS = 28;
+
+    // Days with readings inside any plan's summer season, from aggregateMonthly() output.
+    // Returns { days, minDays, enough }; minDays of 0 turns the check off.
+    function checkSummerCoverage(monthlyUsage, minDays = MIN_SUMMER_DAYS) {
+        const days = new Set();
+        Object.values(monthlyUsage).forEach(m => PLAN_IDS.forEach(id => m.plans[id].summerDays.forEach(day => days.add(day))));
+        return { days: days.size, minDays, enough: days.size >= minDays };
+    }
+
+    // Minutes each reading covers: the shorter of the gaps to its neighbours, so a file that
+    // switches from hourly to 15-minute readings gets both right and a gap in the data doesn't
+    // stretch the reading before it. A lone reading is taken as hourly. Readings marked
+    // repeated (the second pass through the hour clocks repeat in November) carry the same
+    // clock times as the first pass, so they are measured only against each other, within the hour.
+    function readingMinutes(records) {
+        const minutes = new Array(records.length);
+        [false, true].forEach(repeated => {
+            const at = records.map((r, i) => i).filter(i => Boolean(records[i].repeated) === repeated);
+            const limit = repeated ? 60 : Infinity;
+            const gap = k => {
+                const minutesApart = (records[at[k + 1]].dt - records[at[k]].dt) / (1000 * 60);
+                return minutesApart <= limit ? minutesApart : Infinity;
+            };
+            at.forEach((i, k) => {
+                const shortest = Math.min(k > 0 ? gap(k - 1) : Infinity, k < at.length - 1 ? gap(k) : Infinity);
+                minutes[i] = isFinite(shortest) ? shortest : 60;
+            });
+        });
+        return minutes;
+    }
+
+    function detectIntervalMinutes(records) {
+        const diffs = [];
+        for (let i = 0; i < records.length - 1; i++) {
+            diffs.push((records[i + 1].dt - records[i].dt) / (1000 * 60));
+        }
+        return median(diffs);
+    }
+
+    // Readings missing from a sorted series: wherever the next reading comes more than one and
+    // a half intervals after the last, the intervals between are missing, except a slot in the
+    // hour clocks skip in March. Returns { gaps: [{ from, to, missing }], months }: each gap's
+    // first missing interval, the reading that ends it and how many intervals it lacks, and
+    // months as { 'YYYY-MM': { missing, gaps } } with each gap's intervals counted in the month
+    // they fall in.
+    function findGaps(series, minutes = detectIntervalMinutes(series) || 60) {
+        const step = minutes * 60000;
+        const gaps = [];
+        const months = {};
+        for (let i = 0; i < series.length - 1; i++) {
+            const last = series[i].dt.getTime();
+            const next = series[i + 1].dt.getTime();
+            if (next - last <= step * 1.5) continue;
+            let missing = 0;
+            const touched = new Set();
+            for (let t = last + step; t < next - step / 2; t += step) {
+                const slot = new Date(t);
+                if (clockChange(slot) === 'skipped') continue;
+                const key = `${slot.getFullYear()}-${String(slot.getMonth() + 1).padStart(2, '0')}`;
+                const month = months[key] || (months[key] = { missing: 0, gaps: 0 });
+                month.missing++;
+                if (!touched.has(key)) month.gaps++;
+                touched.add(key);
+                missing++;
+            }
+            if (missing > 0) gaps.push({ from: new Date(last + step), to: new Date(next), missing });
+        }
+        return { gaps, months };
+    }
+
+    // How fillGaps() can estimate the readings findGaps() reports missing, each with the phrase
+    // the notes use for it
+    const GAP_FILL_METHODS = {
+        zero: 'as zero',
+        'previous-day-same-hour': 'from the same time the day before',
+        linear: 'on a straight line between the readings either side',
+        'monthly-average': "from the month's average for that time of day"
+    };
+
+    // A sorted series with the readings findGaps() reports missing filled in by method, each new
+    // reading marked estimated and filled. previous-day-same-hour looks back a day at a time,
+    // filled readings included, so a long gap repeats the last day before it; with no earlier
+    // day it takes the monthly average. The monthly average falls back to that time of day over
+    // the whole series when the month has no readings at that time. Gaps longer than maxHours
+    // are left open. Returns { series, added, left }: the new series, the readings added, and
+    // how many gaps were left open.
+    function fillGaps(series, method, { minutes = detectIntervalMinutes(series) || 60, maxHours = Infinity } = {}) {
+        if (!GAP_FILL_METHODS[method]) {
+            throw new Error(`Unknown gap fill method "${method}"; use one of ${Object.keys(GAP_FILL_METHODS).join(', ')}`);
+        }
+        const step = minutes * 60000;
+        const byTime = new Map(series.map(r => [r.dt.getTime(), r.kwh]));
+        const sums = {};
+        const timeOfDay = dt => dt.getHours() * 60 + dt.getMinutes();
+        const tally = (key, kwh) => {
+            const s = sums[key] || (sums[key] = { kwh: 0, n: 0 });
+            s.kwh += kwh;
+            s.n++;
+        };
+        series.forEach(({ dt, kwh }) => {
+            tally(`${dt.getFullYear()}-${dt.getMonth()} ${timeOfDay(dt)}`, kwh);
+            tally(timeOfDay(dt), kwh);
+        });
+        const mean = key => (sums[key] ? sums[key].kwh / sums[key].n : undefined);
+        const monthly = dt => mean(`${dt.getFullYear()}-${dt.getMonth()} ${timeOfDay(dt)}`) ?? mean(timeOfDay(dt)) ?? 0;
+        const firstTime = series.length > 0 ? series[0].dt.getTime() : 0;
+        const dayBefore = dt => {
+            for (let d = 1; ; d++) {
+                const earlier = new Date(dt.getFullYear(), dt.getMonth(), dt.getDate() - d, dt.getHours(), dt.getMinutes()).getTime();
+                if (earlier < firstTime) return monthly(dt);
+                if (byTime.has(earlier)) return byTime.get(earlier);
+            }
+        };
+
+        const added = [];
+        let left = 0;
+        findGaps(series, minutes).gaps.forEach(gap => {
+            const before = gap.from.getTime() - step;
+            const after = gap.to.getTime();
+            if (after - before > maxHours * 3600000) {
+                left++;
+                return;
+            }
+            const steps = Math.round((after - before) / step);
+            for (let k = 1; k < steps; k++) {
+                const dt = new Date(before + k * step);
+                if (clockChange(dt) === 'skipped') continue;
+                const kwh = method === 'zero' ? 0
+                    : method === 'linear' ? byTime.get(before) + (byTime.get(after) - byTime.get(before)) * k / steps
+                        : method === 'monthly-average' ? monthly(dt)
+                            : dayBefore(dt);
+                byTime.set(dt.getTime(), kwh);
+                added.push({ dt, kwh, estimated: true, filled: true });
+            }
+        });
+        return { series: [...series, ...added].sort((a, b) => a.dt - b.dt), added, left };
+    }
+
+    // Billing takes each reading's timestamp as the start of its interval. Some exports label
+    // an interval by its end instead, which moves every reading an hour late and into the
+    // wrong TOU period. Returns { label: 'start'|'end', reason }, from the timestamp column's
+    // header ("Interval End", "Hour Ending") when it says, then from clock times written as
+    // 24:00 (midnight24: how many), then from readings that run from one interval past
+    // midnight to midnight.
+    function detectIntervalLabel(records, { header = '', midnight24 = 0 } = {}) {
+        if (/\bend(ing)?\b/i.test(header)) return { label: 'end', reason: `the column is named "${header}"` };
+        if (/\b(start|begin)/i.test(header)) return { label: 'start', reason: `the column is named "${header}"` };
+        if (midnight24 > 0) return { label: 'end', reason: `${midnight24} timestamp(s) read 24:00` };
+        if (records.length < 2) return { label: 'start', reason: 'too few readings to tell' };
+        const minutes = detectIntervalMinutes(records);
+        const clock = dt => dt.getHours() * 60 + dt.getMinutes();
+        const first = records[0].dt;
+        const last = records[records.length - 1].dt;
+        if (minutes > 0 && minutes < 1440 && clock(first) === minutes && clock(last) === 0) {
+            return { label: 'end', reason: `readings run from ${String(first.getHours()).padStart(2, '0')}:${String(first.getMinutes()).padStart(2, '0')} to midnight` };
+        }
+        return { label: 'start', reason: 'nothing suggests otherwise' };
+    }
+
+    // Records relabeled from interval end to interval start, each moved back one interval
+    function toIntervalStart(records, minutes = detectIntervalMinutes(records) || 60) {
+        return records.map(r => ({ ...r, dt: new Date(r.dt.getTime() - minutes * 60 * 1000) }));
+    }
+
+    // --- Calendar helpers ---
+    // Day and year arithmetic on local wall-clock dates, so leap days and DST shifts
+    // never turn into fractional days or off-by-one-day years.
+
+    function daysInMonth(year, monthIndex) {
+        return new Date(year, monthIndex + 1, 0).getDate();
+    }
+
+    // Same wall-clock time n years later (or earlier); Feb 29 falls back to Feb 28
+    function addYears(dt, n) {
+        const year = dt.getFullYear() + n;
+        const day = Math.min(dt.getDate(), daysInMonth(year, dt.getMonth()));
+        return new Date(year, dt.getMonth(), day, dt.getHours(), dt.getMinutes());
+    }
+
+    // Elapsed days by the wall clock (a 23-hour DST day still counts as one day)
+    function daysBetween(a, b) {
+        const wall = dt => Date.UTC(dt.getFullYear(), dt.getMonth(), dt.getDate(), dt.getHours(), dt.getMinutes());
+        return (wall(b) - wall(a)) / (1000 * 60 * 60 * 24);
+    }
+
+    // Whole calendar years between two timestamps, counted back from end: a year ending
+    // 2025-02-28 starts 2024-02-28, so from 2024-02-29 it is one day short
+    function fullYearsBetween(start, end) {
+        let years = end.getFullYear() - start.getFullYear();
+        while (years > 0 && addYears(end, -years) < start) years--;
+        return years;
+    }
+
+    // --- Time zones ---
+
+    // The tariffs are written in Georgia clock time, daylight saving included. Readings are
+    // kept as Dates whose local fields (getHours() and so on) hold that wall-clock time.
+    const GEORGIA_TIME_ZONE = 'America/New_York';
+
+    const zoneFormats = new Map(); // time zone -> Intl.DateTimeFormat
+    const zoneOffsets = new Map(); // "zone hour" -> minutes ahead of UTC
+
+    // Minutes a time zone's clock runs ahead of UTC at an instant (ms); -300 for EST. Zone
+    // offsets only change on the hour in the zones this is used with, so one lookup per hour.
+    function zoneOffsetMinutes(instant, timeZone) {
+        const key = `${timeZone} ${Math.floor(instant / 3600000)}`;
+        if (zoneOffsets.has(key)) return zoneOffsets.get(key);
+        if (!zoneFormats.has(timeZone)) {
+            zoneFormats.set(timeZone, new Intl.DateTimeFormat('en-US', {
+                timeZone, hourCycle: 'h23', year: 'numeric', month: 'numeric', day: 'numeric', hour: 'numeric', minute: 'numeric'
+            }));
+        }
+        const parts = {};
+        zoneFormats.get(timeZone).formatToParts(new Date(Math.floor(instant / 3600000) * 3600000))
+            .forEach(({ type, value }) => { parts[type] = Number(value); });
+        const offset = Math.round((Date.UTC(parts.year, parts.month - 1, parts.day, parts.hour, parts.minute) - Math.floor(instant / 3600000) * 3600000) / 60000);
+        zoneOffsets.set(key, offset);
+        return offset;
+    }
+
+    // The instant a wall-clock time (the local fields of dt) names in a time zone. On the
+    // 25-hour day the repeated hour names two instants: the first unless later is set, and
+    // ambiguous is true. On the 23-hour day the skipped hour names none: skipped is true and
+    // the time is read with the offset from before the change (2:30 becomes 3:30 daylight time).
+    function wallClockInstant(dt, timeZone, later = false) {
+        const asUtc = Date.UTC(dt.getFullYear(), dt.getMonth(), dt.getDate(), dt.getHours(), dt.getMinutes(), dt.getSeconds());
+        const before = asUtc - zoneOffsetMinutes(asUtc - 43200000, timeZone) * 60000;
+        const after = asUtc - zoneOffsetMinutes(asUtc + 43200000, timeZone) * 60000;
+        const fits = [before, after].filter(t => t + zoneOffsetMinutes(t, timeZone) * 60000 === asUtc);
+        if (fits.length === 0) return { instant: before, ambiguous: false, skipped: true };
+        const unique = [...new Set(fits)].sort((a, b) => a - b);
+        return { instant: later ? unique[unique.length - 1] : unique[0], ambiguous: unique.length > 1, skipped: false };
+    }
+
+    // 'repeated' for a wall-clock time that occurs twice when the clocks fall back, 'skipped' for
+    // one that never occurs when they spring forward, else null
+    function clockChange(dt, timeZone = GEORGIA_TIME_ZONE) {
+        const { ambiguous, skipped } = wallClockInstant(dt, timeZone);
+        return skipped ? 'skipped' : ambiguous ? 'repeated' : null;
+    }
+
+    // Georgia wall-clock time of an instant (ms), as a Date with local fields set to it
+    function georgiaClock(instant) {
+        const wall = new Date(instant + zoneOffsetMinutes(instant, GEORGIA_TIME_ZONE) * 60000);
+        return new Date(wall.getUTCFullYear(), wall.getUTCMonth(), wall.getUTCDate(), wall.getUTCHours(), wall.getUTCMinutes(), wall.getUTCSeconds());
+    }
+
+    // A reading moved to the Georgia clock at an instant, marked repeated when the instant
+    // falls in the second pass through the hour clocks repeat in November
+    function atGeorgiaInstant(r, instant) {
+        const dt = georgiaClock(instant);
+        const later = wallClockInstant(dt, GEORGIA_TIME_ZONE, true);
+        const moved = { ...r, dt };
+        delete moved.repeated;
+        if (later.ambiguous && later.instant === instant) moved.repeated = true;
+        return moved;
+    }
+
+    // A sorted series whose clock times are in timeZone (an IANA name such as 'UTC' or
+    // 'America/Chicago'), moved to Georgia clock time. Readings marked zoned carry an exact
+    // instant (their timestamp had a Z or offset) and are only moved to Georgia time. A reading
+    // that repeats an ambiguous time on a 25-hour day is taken as the second occurrence, so the
+    // hour counts twice as it did on the meter; readings in Georgia's second pass through that
+    // hour are marked repeated. In Georgia time the day has 25 hours or, from a
+    // zone without daylight saving, the spring day has 23. Throws a RangeError for an unknown
+    // zone. Returns { series, skipped }: skipped counts readings timed in an hour the source
+    // zone's clocks skipped, which are read with the offset from before the change unless the
+    // zone is Georgia's.
+    function toGeorgiaTime(series, timeZone) {
+        let skipped = 0;
+        let previous = null;
+        const moved = series.map(r => {
+            if (r.zoned) return atGeorgiaInstant(r, r.dt.getTime());
+            const repeat = Boolean(r.repeated) || (previous !== null && r.dt.getTime() === previous.dt.getTime());
+            const wall = wallClockInstant(r.dt, timeZone, repeat);
+            if (wall.skipped) skipped++;
+            previous = r;
+            // Already Georgia time; a reading in the skipped hour stays where the file put it
+            if (timeZone === GEORGIA_TIME_ZONE) return repeat && wall.ambiguous ? { ...r, repeated: true } : r;
+            return atGeorgiaInstant(r, wall.instant);
+        });
+        return { series: moved, skipped };
+    }
+
+    function median(values) {
+        if (values.length === 0) return 0;
+        const sorted = [...values].sort((a, b) => a - b);
+        const mid = Math.floor(sorted.length / 2);
+        return sorted.length % 2 ? sorted[mid] : (sorted[mid - 1] + sorted[mid]) / 2;
+    }
+
+    // --- Hourly prices ---
+
+    // All-in $/kWh for one hour: energy rate + fuel recovery, grossed up for taxes & fees.
+    // R-30 has no time periods, so its price is the marginal block for the month's usage so far.
+    function hourlyPrice(planId, dt, monthKwhBefore) {
+        const fcrRate = FUEL_RATES[inSeason(FCR_SEASON, dt) ? 'summer' : 'winter'];
+
+        // Registered plans without declared periods classify hours their own way
+        if (!RATE_PLANS[planId].periods) {
+            const period = classifyHour(planId, dt);
+            return { period, price: (ENERGY_RATES[planId][period] + fcrRate) * TAX_RATE };
+        }
+        const { period, rate } = RateSchedule.rateAt(scheduleFor(planId), dt, monthKwhBefore);
+        return { period, price: (rate + fcrRate) * TAX_RATE };
+    }
+
+    // A plan's live price, for "this hour costs X" displays that have no usage file:
+    //   const reo = RateBilling.plan('nights-and-weekends');
+    //   reo.priceAt(new Date());              // all-in $/kWh right now
+    //   reo.costOf('2025-07-08T15:00', 3.2);  // $ for 3.2 kWh at 3 PM that day (on-peak)
+    // Timestamps are anything new Date() takes. Seasons, the fuel rider's season and HOLIDAYS
+    // are resolved for each timestamp exactly as on a bill. Block-priced plans (R-30) need the month's
+    // kWh so far to find the block; costOf splits kWh that cross into the next block. The
+    // basic service charge and TOU-RD's demand charge aren't per kWh, so neither is included.
+    function plan(name) {
+        const planId = RATE_PLANS[name] ? name : findPlanId(name);
+        if (!planId) throw new Error(`Unknown rate plan: ${name}`);
+        const toDate = timestamp => {
+            const dt = timestamp instanceof Date ? timestamp : new Date(timestamp);
+            if (isNaN(dt.getTime())) throw new Error(`Unreadable timestamp: ${timestamp}`);
+            return dt;
+        };
+        // kWh (month to date) where the price can change: block limits and the end of a credit
+        const blockEdges = dt => {
+            const energy = ENERGY_RATES[planId];
+            if (!(energy.summer && energy.summer.rates)) return [];
+            const blocks = inSeason(RATE_PLANS[planId].summer, dt) ? energy.summer : energy.winter;
+            return [...blocks.limits, ...(blocks.credit ? [blocks.credit.upTo] : [])].sort((a, b) => a - b);
+        };
+
+        function priceAt(timestamp, monthKwhBefore = 0) {
+            return hourlyPrice(planId, toDate(timestamp), monthKwhBefore).price;
+        }
+
+        function costOf(timestamp, kwh, monthKwhBefore = 0) {
+            const dt = toDate(timestamp);
+            const edges = blockEdges(dt);
+            let cost = 0;
+            let used = monthKwhBefore;
+            let left = kwh;
+            while (left > 0) {
+                const edge = edges.find(limit => limit > used);
+                const chunk = edge === undefined ? left : Math.min(left, edge - used);
+                cost += chunk * hourlyPrice(planId, dt, used).price;
+                used += chunk;
+                left -= chunk;
+            }
+            return cost;
+        }
+
+        return { id: planId, name: RATE_PLANS[planId].name, priceAt, costOf };
+    }
+
+    // --- Usage files ---
+
+    // Likely field separator of delimited text: comma, semicolon, tab or pipe, whichever
+    // splits the most of the first lines into the same number of cells (comma on a tie).
+    // Lines of prose before the header seldom agree with each other, so they don't sway it.
+    function detectDelimiter(text) {
+        const lines = text.split(/\r?\n/).filter(line => line.trim()).slice(0, 50);
+        let best = { delimiter: ',', lines: 0 };
+        [',', ';', '\t', '|'].forEach(delimiter => {
+            const counts = {};
+            lines.forEach(line => {
+                const n = line.replace(/"[^"]*"/g, '').split(delimiter).length - 1;
+                if (n > 0) counts[n] = (counts[n] || 0) + 1;
+            });
+            const agreeing = Math.max(0, ...Object.values(counts));
+            if (agreeing > best.lines) best = { delimiter, lines: agreeing };
+        });
+        return best.delimiter;
+    }
+
+    // Hourly usage from CSV text with a "YYYY-MM-DD HH:MM" (local time) column followed by
+    // a kWh column, like a GA Power hourly export saved as CSV. Header and blank lines are skipped.
+    // The delimiter is detected unless options.delimiter sets it. options.timestampColumn and
+    // options.kwhColumn name the header cells to read instead, for exports with other layouts
+    // ("Interval End", "Usage (kWh)"); an exact match wins over one that merely contains the name.
+    function parseUsageCsv(text, options = {}) {
+        const delimiter = options.delimiter || detectDelimiter(text);
+        const named = options.timestampColumn || options.kwhColumn;
+        let columns = named ? null : [0, 1];
+        const series = [];
+        text.split(/\r?\n/).forEach(line => {
+            const cells = line.split(delimiter).map(cell => cell.trim().replace(/^"|"$/g, ''));
+            if (!columns) {
+                columns = findNamedColumns(cells, [options.timestampColumn, options.kwhColumn], [0, 1]);
+                return;
+            }
+            const [stamp, value] = columns.map(i => cells[i]);
+            const match = stamp && stamp.match(/^(\d{4})-(\d{2})-(\d{2})[ T](\d{2}):(\d{2})/);
+            const kwh = parseFloat(value);
+            if (!match || isNaN(kwh)) return;
+            const [, y, mo, d, h, mi] = match.map(Number);
+            series.push({ dt: new Date(y, mo - 1, d, h, mi), kwh });
+        });
+        if (!columns) {
+            throw new Error(`No header row with ${[options.timestampColumn, options.kwhColumn].filter(Boolean).map(n => `"${n}"`).join(' and ')}`);
+        }
+        return series.sort((a, b) => a.dt - b.dt);
+    }
+
+    // Indices of the header cells matching each name (case-insensitive; exact first, then
+    // containing), falling back to fallback[i] for a name left empty. null when any named
+    // column is missing from this row.
+    function findNamedColumns(cells, names, fallback) {
+        const lower = cells.map(c => String(c ?? '').trim().toLowerCase());
+        const found = names.map((name, i) => {
+            if (!name) return fallback[i];
+            const wanted = name.trim().toLowerCase();
+            const exact = lower.indexOf(wanted);
+            return exact !== -1 ? exact : lower.findIndex(c => c.includes(wanted));
+        });
+        return found.some(i => i === -1) ? null : found;
+    }
+
+    // Usage from JSON: an array of { "timestamp": ..., "kwh": ... } records, or one record per
+    // line (JSON Lines), as APIs tend to return them. A timestamp is ISO 8601 (with Z or an
+    // offset, or else local time), "YYYY-MM-DD HH:MM" local time, or epoch seconds or
+    // milliseconds. Records missing either field are skipped.
+    function parseUsageJson(text) {
+        const trimmed = text.trim();
+        const records = trimmed.startsWith('[')
+            ? JSON.parse(trimmed)
+            : trimmed.split(/\r?\n/).filter(line => line.trim()).map(line => JSON.parse(line));
+        const series = [];
+        records.forEach(record => {
+            const stamp = record && record.timestamp;
+            const dt = parseTimestamp(stamp);
+            const kwh = parseFloat(record && record.kwh);
+            // Epoch times and ISO times with Z or an offset are exact instants (see toGeorgiaTime)
+            const zoned = typeof stamp === 'number' || /^\d{9,13}(\.\d+)?$|(?:Z|[+-]\d{2}:?\d{2})$/i.test(String(stamp ?? '').trim());
+            if (dt && !isNaN(kwh)) series.push({ dt, kwh, ...(zoned ? { zoned } : {}) });
+        });
+        return series.sort((a, b) => a.dt - b.dt);
+    }
+
+    function parseTimestamp(value) {
+        if (typeof value === 'number' || /^\d{9,13}(\.\d+)?$/.test(String(value ?? '').trim())) {
+            const number = Number(value);
+            return new Date(number > 1e11 ? number : number * 1000);
+        }
+        const text = String(value ?? '').trim();
+        const local = text.match(/^(\d{4})-(\d{2})-(\d{2})[ T](\d{2}):(\d{2})(?::(\d{2}))?$/);
+        if (local) {
+            const [, y, mo, d, h, mi, sec] = local.map(Number);
+            return new Date(y, mo - 1, d, h, mi, sec || 0);
+        }
+        const dt = text ? new Date(text) : null;
+        return dt && !isNaN(dt.getTime()) ? dt : null;
+    }
+
+    // How a timestamp read more than once is settled, from the reading kept so far and the
+    // next copy: keep the first or the last, the larger, add them up, or refuse the data.
+    const DEDUPE_POLICIES = {
+        first: { describe: 'the first reading was kept', resolve: kept => kept },
+        last: { describe: 'the last reading was kept', resolve: (kept, next) => next },
+        max: { describe: 'the larger reading was kept', resolve: (kept, next) => (next.kwh > kept.kwh ? next : kept) },
+        sum: { describe: 'the readings were added together', resolve: (kept, next) => ({ ...kept, kwh: kept.kwh + next.kwh }) },
+        error: {
+            describe: 'duplicates are refused',
+            resolve: kept => {
+                const pad = n => String(n).padStart(2, '0');
+                const dt = kept.dt;
+                throw new Error(`${dt.getFullYear()}-${pad(dt.getMonth() + 1)}-${pad(dt.getDate())} ${pad(dt.getHours())}:${pad(dt.getMinutes())} ` +
+                    'is read more than once (duplicate policy "error")');
+            }
+        }
+    };
+
+    // Several usage series, such as monthly downloads, as one sorted series, with each
+    // timestamp found more than once settled by a DEDUPE_POLICIES policy. The hour clocks
+    // repeat in November (on timeZone's clock) can appear twice in one series, once on
+    // daylight and once on standard time, unless the readings carry their own offset. overlap
+    // is null, or { count, conflicts, from, to, policy }: how many repeats were resolved, how
+    // many of those had a different kWh, and the first and last repeated timestamp.
+    function mergeUsage(seriesList, { policy = 'first', timeZone = GEORGIA_TIME_ZONE } = {}) {
+        if (!DEDUPE_POLICIES[policy]) throw new Error(`Unknown duplicate policy "${policy}"; use one of ${Object.keys(DEDUPE_POLICIES).join(', ')}`);
+        const { resolve } = DEDUPE_POLICIES[policy];
+        const seen = new Map(); // "time occurrence" -> { kwh of the first reading, index in series }
+        const series = [];
+        let overlap = null;
+        seriesList.forEach(list => {
+            const occurrences = new Map();
+            list.forEach(reading => {
+                const at = reading.dt.getTime();
+                const occurrence = occurrences.get(at) || 0;
+                occurrences.set(at, occurrence + 1);
+                // A second reading of the repeated hour within a series is its other occurrence
+                const repeat = occurrence === 1 && !reading.zoned && clockChange(reading.dt, timeZone) === 'repeated' ? 1 : 0;
+                const time = `${at} ${repeat}`;
+                const first = seen.get(time);
+                if (!first) {
+                    seen.set(time, { kwh: reading.kwh, index: series.length });
+                    series.push(repeat ? { ...reading, repeated: true } : reading);
+                    return;
+                }
+                series[first.index] = resolve(series[first.index], reading);
+                if (!overlap) overlap = { count: 0, conflicts: 0, from: reading.dt, to: reading.dt, policy };
+                overlap.count++;
+                if (Math.abs(first.kwh - reading.kwh) > 0.001) overlap.conflicts++;
+                if (reading.dt < overlap.from) overlap.from = reading.dt;
+                if (reading.dt > overlap.to) overlap.to = reading.dt;
+            });
+        });
+        return { series: series.sort((a, b) => a.dt - b.dt), overlap };
+    }
+
+    // Anonymized profiles: usage with the dates taken out, safe to post when asking "which plan
+    // for this shape?". Each reading keeps its day number (0 = first day), clock time, weekday
+    // and month, which is what the tariffs price on, and kWh is multiplied by a factor that is
+    // not written down. restoreAnonymized() places the days back on a stand-in calendar
+    // (ANONYMIZED_YEAR), starting on the first date with the same weekday in the same month,
+    // so season and holiday edges can land up to six days off.
+    const ANONYMIZED_HEADER = ['Day', 'Time', 'Weekday', 'Month', 'kWh'];
+    const ANONYMIZED_YEAR = 2023;
+    const WEEKDAY_ABBR = ['Sun', 'Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat'];
+
+    // CSV text of an anonymized profile of a sorted series. The scale factor falls between
+    // 0.8 and 1.25; random is there so a script can make the output repeatable.
+    function anonymizeUsage(series, { random = Math.random } = {}) {
+        const factor = 0.8 + random() * 0.45;
+        const first = series[0].dt;
+        const firstDay = Date.UTC(first.getFullYear(), first.getMonth(), first.getDate());
+        const pad = n => String(n).padStart(2, '0');
+        const lines = ['# Anonymized usage profile: dates are replaced by day numbers and kWh is rescaled', ANONYMIZED_HEADER.join(',')];
+        series.forEach(({ dt, kwh }) => {
+            const day = Math.round((Date.UTC(dt.getFullYear(), dt.getMonth(), dt.getDate()) - firstDay) / 86400000);
+            lines.push([day, `${pad(dt.getHours())}:${pad(dt.getMinutes())}`, WEEKDAY_ABBR[dt.getDay()], dt.getMonth() + 1, Number((kwh * factor).toFixed(4))].join(','));
+        });
+        return lines.join('\n') + '\n';
+    }
+
+    // Series from the data rows of an anonymized profile ([day, time, weekday, month, kWh]
+    // cells below the header). Rows that don't parse are skipped.
+    function restoreAnonymized(rows) {
+        const valid = rows.filter(r => r && r.length >= 5 && /^\d+$/.test(String(r[0]).trim()) && /^\d{1,2}:\d{2}$/.test(String(r[1]).trim()));
+        if (valid.length === 0) return [];
+        const [day0, , weekday0, month0] = valid[0];
+        const month = Number(month0) - 1;
+        const firstWeekday = new Date(ANONYMIZED_YEAR, month, 1).getDay();
+        const wanted = WEEKDAY_ABBR.indexOf(String(weekday0).trim());
+        const startDate = 1 + ((wanted === -1 ? firstWeekday : wanted) - firstWeekday + 7) % 7 - Number(day0);
+        const series = [];
+        valid.forEach(([day, time, , , kwh]) => {
+            const [h, mi] = String(time).trim().split(':').map(Number);
+            const value = parseFloat(kwh);
+            if (!isNaN(value)) series.push({ dt: new Date(ANONYMIZED_YEAR, month, startDate + Number(day), h, mi), kwh: value });
+        });
+        return series.sort((a, b) => a.dt - b.dt);
+    }
+
+    // Annual load in the 8760 layout SAM, REopt and most energy models read: kWh for each hour
+    // of a 365-day year, January 1 00:00 to December 31 23:00 on Eastern Standard Time all year
+    // (UTC-5, the clock those tools and dispatchSeries() assume), with February 29 left out.
+    // Readings are moved off the Georgia clock first, so summer hours sit an hour earlier than
+    // on the meter and no slot is skipped in March or doubled in November. Each slot takes the
+    // latest reading for that date and hour in the series (sub-hourly readings are summed into
+    // their hour). A slot with no reading is filled from the same hour on the nearest day that
+    // has one, up to FILL_8760_DAYS away, else with the series' average for that hour. Filled
+    // slots are flagged, and so are slots holding a reading fillGaps() added.
+    // Returns { hours: [{ month, day, hour, kwh, filled }], filled }.
+    const FILL_8760_DAYS = 14;
+
+    function build8760(series) {
+        const slotOf = (month, day, hour) => (Date.UTC(2023, month - 1, day) - Date.UTC(2023, 0, 1)) / 3600000 + hour;
+        const kwh = new Array(8760).fill(null);
+        const latest = new Array(8760).fill(null); // "YYYY-MM-DD" the slot's reading came from
+        const gapFilled = new Array(8760).fill(false);
+        const hourSums = new Array(24).fill(0);
+        const hourCounts = new Array(24).fill(0);
+        series.forEach(({ dt: clock, kwh: value, filled, repeated }) => {
+            // UTC fields of dt hold the Eastern Standard Time clock
+            const dt = new Date(wallClockInstant(clock, GEORGIA_TIME_ZONE, Boolean(repeated)).instant - 5 * 3600000);
+            if (dt.getUTCMonth() === 1 && dt.getUTCDate() === 29) return;
+            const slot = slotOf(dt.getUTCMonth() + 1, dt.getUTCDate(), dt.getUTCHours());
+            const date = `${dt.getUTCFullYear()}-${String(dt.getUTCMonth() + 1).padStart(2, '0')}-${String(dt.getUTCDate()).padStart(2, '0')}`;
+            if (latest[slot] === null || date > latest[slot]) {
+                latest[slot] = date;
+                kwh[slot] = 0;
+                gapFilled[slot] = false;
+            }
+            if (date === latest[slot]) {
+                kwh[slot] += value;
+                gapFilled[slot] = gapFilled[slot] || Boolean(filled);
+            }
+            hourSums[dt.getUTCHours()] += value;
+            hourCounts[dt.getUTCHours()]++;
+        });
+
+        const hours = [];
+        let filled = 0;
+        for (let slot = 0; slot < 8760; slot++) {
+            const date = new Date(Date.UTC(2023, 0, 1) + slot * 3600000);
+            const entry = { month: date.getUTCMonth() + 1, day: date.getUTCDate(), hour: date.getUTCHours(), kwh: kwh[slot], filled: gapFilled[slot] };
+            if (entry.kwh === null) {
+                let near = null;
+                for (let d = 1; d <= FILL_8760_DAYS && near === null; d++) {
+                    near = [slot - d * 24, slot + d * 24].map(s => (s + 8760) % 8760).map(s => kwh[s]).find(v => v !== null) ?? null;
+                }
+                const hourly = hourCounts[entry.hour] ? hourSums[entry.hour] / hourCounts[entry.hour] : 0;
+                entry.kwh = near !== null ? near : hourly;
+                entry.filled = true;
+            }
+            if (entry.filled) filled++;
+            hours.push(entry);
+        }
+        return { hours, filled };
+    }
+
+    // Dispatch results from SAM or REopt: a year of power values, one per time step from
+    // January 1 00:00, usually 8,760 hourly steps. Both tools run on standard time all year, so
+    // each step starts at an exact instant on Eastern Standard Time (UTC-5). DISPATCH_YEAR is the
+    // calendar used when the results don't name one (or name one of the wrong length).
+    const DISPATCH_YEAR = 2023;
+
+    // kW per step as a series of { dt, kwh, zoned: true }, dt the exact instant the step starts
+    // (toGeorgiaTime() moves it to the Georgia clock), or null when the step count isn't a whole
+    // number of steps per hour for a 365- or 366-day year. Negative values (power sent to the
+    // grid) are billed as zero. Returns { series, year, stepMinutes }.
+    function dispatchSeries(kw, year) {
+        const days = kw.length % 8784 === 0 && kw.length % 8760 !== 0 ? 366 : 365;
+        const perHour = kw.length / (days * 24);
+        if (kw.length === 0 || !Number.isInteger(perHour)) return null;
+        const leap = y => new Date(y, 1, 29).getMonth() === 1;
+        const calendarYear = year && leap(year) === (days === 366) ? year : (days === 366 ? DISPATCH_YEAR + 1 : DISPATCH_YEAR);
+        const stepMinutes = 60 / perHour;
+        const series = kw.map((value, i) => ({
+            dt: new Date(Date.UTC(calendarYear, 0, 1, 5, i * stepMinutes)),
+            kwh: Math.max(0, Number(value) || 0) / perHour,
+            zoned: true
+        }));
+        return { series, year: calendarYear, stepMinutes };
+    }
+
+    // The series a REopt results file (the API's JSON response) holds: grid kW to the load and
+    // to the battery, the site load before any DER, and the load profile's year. Reads the v3
+    // layout (outputs.ElectricUtility) and the older one (outputs.Scenario.Site). Returns
+    // { grid, load, year } with load null when missing, or null for other JSON.
+    function readReoptResults(results) {
+        const outputs = results && results.outputs;
+        if (!outputs || typeof outputs !== 'object') return null;
+        const inputs = results.inputs || {};
+        const sum = (a, b) => a.map((value, i) => value + ((b && b[i]) || 0));
+        if (outputs.ElectricUtility && Array.isArray(outputs.ElectricUtility.electric_to_load_series_kw)) {
+            const utility = outputs.ElectricUtility;
+            return {
+                grid: sum(utility.electric_to_load_series_kw, utility.electric_to_storage_series_kw),
+                load: (outputs.ElectricLoad && outputs.ElectricLoad.load_series_kw) || null,
+                year: (inputs.ElectricLoad && inputs.ElectricLoad.year) || null
+            };
+        }
+        const site = outputs.Scenario && outputs.Scenario.Site;
+        const tariff = site && site.ElectricTariff;
+        if (tariff && Array.isArray(tariff.year_one_to_load_series_kw)) {
+            const profile = inputs.Scenario && inputs.Scenario.Site && inputs.Scenario.Site.LoadProfile;
+            return {
+                grid: sum(tariff.year_one_to_load_series_kw, tariff.year_one_to_battery_series_kw),
+                load: (site.LoadProfile && site.LoadProfile.year_one_electric_load_series_kw) || null,
+                year: (profile && profile.year) || null
+            };
+        }
+        return null;
+    }
+
+    // --- Preprocessing pipelines ---
+    // A pipeline file is JSON listing transforms to run, in order, before billing:
+    //   { "steps": [{ "step": "dedupe", "policy": "first" },                see DEDUPE_POLICIES
+    //               { "step": "shift", "minutes": -60 },                    clock or time-zone correction
+    //               { "step": "fill-gaps", "max_hours": 6, "method": "linear" }, see GAP_FILL_METHODS
+    //               { "step": "scale", "factor": 1.1 },
+    //               { "step": "add-load", "kw": 7.2, "hours": [23, 3] }] }  hours are [from, to), may wrap midnight
+    // Each step can appear any number of times. applyPipeline returns the new series and a log line per step.
+
+    const PIPELINE_STEPS = {
+        dedupe: {
+            keys: ['policy'],
+            check: (step, where, errors) => {
+                if (step.policy !== undefined && !DEDUPE_POLICIES[step.policy]) errors.push(`${where}.policy must be one of ${Object.keys(DEDUPE_POLICIES).join(', ')}`);
+            },
+            run: (series, { policy = 'first' }) => {
+                const { series: kept, overlap } = mergeUsage([series], { policy });
+                return { series: kept, detail: overlap ? `${overlap.count} repeated reading(s) resolved, ${DEDUPE_POLICIES[policy].describe}` : 'no repeated readings' };
+            }
+        },
+        shift: {
+            keys: ['minutes'],
+            check: (step, where, errors) => {
+                if (typeof step.minutes !== 'number' || !isFinite(step.minutes)) errors.push(`${where}.minutes must be a number`);
+            },
+            run: (series, { minutes }) => ({
+                series: series.map(r => ({ ...r, dt: new Date(r.dt.getTime() + minutes * 60 * 1000) })),
+                detail: `timestamps moved ${minutes > 0 ? '+' : ''}${minutes} min`
+            })
+        },
+        'fill-gaps': {
+            keys: ['max_hours', 'method'],
+            check: (step, where, errors) => {
+                if (step.max_hours !== undefined && !(typeof step.max_hours === 'number' && step.max_hours > 0)) {
+                    errors.push(`${where}.max_hours must be a positive number`);
+                }
+                if (step.method !== undefined && !GAP_FILL_METHODS[step.method]) {
+                    errors.push(`${where}.method must be one of ${Object.keys(GAP_FILL_METHODS).join(', ')}`);
+                }
+            },
+            run: (series, { max_hours: maxHours = 24, method = 'linear' }) => {
+                const { series: out, added, left } = fillGaps(series, method, { maxHours });
+                return {
+                    series: out,
+                    detail: `${added.length} reading(s) filled ${GAP_FILL_METHODS[method]}` + (left > 0 ? `, ${left} gap(s) over ${maxHours} h left open` : '')
+                };
+            }
+        },
+        scale: {
+            keys: ['factor'],
+            check: (step, where, errors) => {
+                if (!(typeof step.factor === 'number' && isFinite(step.factor) && step.factor >= 0)) errors.push(`${where}.factor must be a non-negative number`);
+            },
+            run: (series, { factor }) => ({
+                series: series.map(r => ({ ...r, kwh: r.kwh * factor })),
+                detail: `kWh x ${factor}`
+            })
+        },
+        'add-load': {
+            keys: ['kw', 'hours'],
+            check: (step, where, errors) => {
+                if (!(typeof step.kw === 'number' && isFinite(step.kw) && step.kw >= 0)) errors.push(`${where}.kw must be a non-negative number`);
+                const hours = step.hours;
+                if (hours !== undefined && !(Array.isArray(hours) && hours.length === 2 && hours.every(h => Number.isInteger(h) && h >= 0 && h <= 24))) {
+                    errors.push(`${where}.hours must be [from, to] with hours 0-24`);
+                }
+            },
+            run: (series, { kw, hours = [0, 24] }) => {
+                const [from, to] = hours;
+                const inHours = h => (from <= to ? h >= from && h < to : h >= from || h < to);
+                const kwhPerReading = kw * (detectIntervalMinutes(series) || 60) / 60;
+                return {
+                    series: series.map(r => (inHours(r.dt.getHours()) ? { ...r, kwh: r.kwh + kwhPerReading } : r)),
+                    detail: `${kw} kW added ${from === 0 && to === 24 ? 'all day' : `${from}:00-${to}:00`}`
+                };
+            }
+        }
+    };
+
+    function validatePipeline(config) {
+        const isObject = v => v !== null && typeof v === 'object' && !Array.isArray(v);
+        if (!isObject(config)) return ['expected a JSON object'];
+        const errors = [];
+        Object.keys(config).filter(k => k !== 'steps').forEach(k => errors.push(`unknown key "${k}" in the file`));
+        if (!Array.isArray(config.steps)) return [...errors, 'steps must be a list'];
+        config.steps.forEach((step, i) => {
+            const where = `steps[${i}]`;
+            const kind = isObject(step) ? PIPELINE_STEPS[step.step] : null;
+            if (!kind) {
+                errors.push(`${where}.step must be one of ${Object.keys(PIPELINE_STEPS).join(', ')}`);
+                return;
+            }
+            Object.keys(step).filter(k => k !== 'step' && !kind.keys.includes(k))
+                .forEach(k => errors.push(`unknown key "${k}" in ${where}`));
+            if (kind.check) kind.check(step, where, errors);
+        });
+        return errors;
+    }
+
+    // Run a pipeline file's steps over a sorted series. Returns { series, log }, where log has
+    // one { step, detail } per step in the order they ran.
+    function applyPipeline(series, config) {
+        const errors = validatePipeline(config);
+        if (errors.length > 0) {
+            throw new Error(`Invalid pipeline file: ${errors.join('; ')}`);
+        }
+        const log = [];
+        const result = config.steps.reduce((current, step) => {
+            const { series: next, detail } = PIPELINE_STEPS[step.step].run(current, step);
+            log.push({ step: step.step, detail });
+            return next.sort((a, b) => a.dt - b.dt);
+        }, [...series]);
+        return { series: result, log };
+    }
+
+    // --- Rates files ---
+    // A rates file is JSON in the exportRates() layout:
+    //   { "fuel": { "summer", "winter" },
+    //     "windows": { "<name>": TOU window, see TOU_WINDOWS },
+    //     "plans": { "<plan id>": { "basic_per_day", "demand_per_kw" and "demand_window_minutes" (TOU-RD),
+    //                              "last_verified",
+    //                              "periods": [{ "period", "window" }] (TOU plans),
+    //                              "energy": { period: $/kWh } or, for R-30, { "summer"|"winter": season } } },
+    //     "holidays": { "add": ["YYYY-MM-DD", ...], "remove": ["YYYY-MM-DD", ...] } }
+    // Any part may be left out to keep the built-in value, so a file can hold just the prices that changed.
+    // A file can reshape a TOU plan's hours with new windows, but only over periods the plan already prices.
+
+    function exportRates() {
+        const plans = {};
+        PLAN_IDS.forEach(id => {
+            const service = SERVICE_RATES[id];
+            plans[id] = {
+                basic_per_day: service.basicPerDay,
+                ...(service.demandPerKw !== undefined ? { demand_per_kw: service.demandPerKw } : {}),
+                ...(service.demandWindowMinutes !== undefined ? { demand_window_minutes: service.demandWindowMinutes } : {}),
+                ...(id !== 'r30' && RATE_PLANS[id].periods ? { periods: JSON.parse(JSON.stringify(RATE_PLANS[id].periods)) } : {}),
+                energy: JSON.parse(JSON.stringify(ENERGY_RATES[id])),
+                last_verified: RATE_PLANS[id].lastVerified
+            };
+        });
+        const builtIn = builtInHolidays();
+        const holidays = {
+            add: HOLIDAYS.filter(date => !builtIn.includes(date)),
+            remove: builtIn.filter(date => !HOLIDAYS.includes(date))
+        };
+        return { fuel: { ...FUEL_RATES }, windows: JSON.parse(JSON.stringify(TOU_WINDOWS)), plans, holidays };
+    }
+
+    // Built-in rates, so every loadRates() starts from the published tariffs
+    const DEFAULT_RATES = JSON.stringify(exportRates());
+
+    // Replace the current rates with a rates file. Nothing is applied if any value is malformed.
+    function loadRates(config) {
+        const errors = validateRates(config);
+        if (errors.length > 0) {
+            throw new Error(`Invalid rates file: ${errors.join('; ')}`);
+        }
+        resetRates();
+        applyRates(config);
+    }
+
+    function resetRates() {
+        Object.keys(TOU_WINDOWS).forEach(name => delete TOU_WINDOWS[name]);
+        Object.assign(TOU_WINDOWS, REGISTERED_WINDOWS);
+        applyRates(defaultRates());
+    }
+
+    // The built-in rates, in the exportRates() layout
+    function defaultRates() {
+        return JSON.parse(DEFAULT_RATES);
+    }
+
+    // Run fn with a rates file loaded, then put the current rates back
+    function withRates(config, fn) {
+        const saved = exportRates();
+        loadRates(config);
+        try {
+            return fn();
+        } finally {
+            loadRates(saved);
+        }
+    }
+
+    // Rate-by-rate differences between two rates files; anything a file leaves out is the
+    // built-in value. Returns [{ what, from, to, changePct }], where from/to are null for a
+    // rate only one side has and changePct is null for non-numeric changes (a moved window).
+    function diffRates(oldConfig, newConfig) {
+        const before = flattenRates(withRates(oldConfig, exportRates));
+        const after = flattenRates(withRates(newConfig, exportRates));
+        const paths = [...new Set([...Object.keys(before), ...Object.keys(after)])];
+        return paths
+            .filter(path => JSON.stringify(before[path]) !== JSON.stringify(after[path]))
+            .map(path => {
+                const from = before[path] === undefined ? null : before[path];
+                const to = after[path] === undefined ? null : after[path];
+                const numeric = typeof from === 'number' && typeof to === 'number' && from !== 0;
+                return { what: describeRatePath(path), from, to, changePct: numeric ? (to - from) / from * 100 : null };
+            });
+    }
+
+    // "plans.r30.energy.summer.rates[1]" -> value, for every number in a rates file.
+    // Windows and period lists are kept whole, since only the whole shape means anything.
+    function flattenRates(value, path = '', out = {}) {
+        const opaque = /^windows\.[^.]+$|\.periods$|^holidays\.(add|remove)$/.test(path);
+        if (value !== null && typeof value === 'object' && !opaque) {
+            Object.entries(value).forEach(([k, v]) => {
+                flattenRates(v, Array.isArray(value) ? `${path}[${k}]` : path ? `${path}.${k}` : k, out);
+            });
+        } else {
+            out[path] = value;
+        }
+        return out;
+    }
+
+    function describeRatePath(path) {
+        const parts = path.split('.');
+        if (parts[0] === 'fuel') return `Fuel recovery, ${parts[1]} ($/kWh)`;
+        if (parts[0] === 'windows') return `TOU window "${parts[1]}"`;
+        if (parts[0] === 'holidays') return parts[1] === 'add' ? 'Holidays added' : 'Holidays removed';
+        const plan = RATE_PLANS[parts[1]] ? RATE_PLANS[parts[1]].name : parts[1];
+        const [field, ...rest] = parts.slice(2);
+        const labels = { basic_per_day: 'basic service ($/day)', demand_per_kw: 'demand charge ($/kW)', last_verified: 'rates verified', periods: 'TOU periods' };
+        if (field !== 'energy') return `${plan} ${labels[field] || field}`;
+        if (rest.length === 1) return `${plan} ${rest[0].replace(/_/g, '-')} energy ($/kWh)`;
+        const block = (rest[1] || '').match(/^(rates|limits)\[(\d+)\]$/);
+        if (block && block[1] === 'rates') return `${plan} ${rest[0]} block ${Number(block[2]) + 1} energy ($/kWh)`;
+        if (block) return `${plan} ${rest[0]} block ${Number(block[2]) + 2} starts at (kWh)`;
+        return `${plan} ${rest[0]} ${rest.slice(1).join(' ').replace('upTo', 'up to (kWh)').replace('rate', 'rate ($/kWh)')}`;
+    }
+
+    function applyRates(config) {
+        Object.assign(FUEL_RATES, config.fuel || {});
+        if (config.holidays) {
+            const { add = [], remove = [] } = config.holidays;
+            const dates = builtInHolidays().filter(date => !remove.includes(date));
+            HOLIDAYS.splice(0, HOLIDAYS.length, ...[...dates, ...add.filter(date => !dates.includes(date))].sort());
+        }
+        Object.assign(TOU_WINDOWS, JSON.parse(JSON.stringify(config.windows || {})));
+        Object.entries(config.plans || {}).forEach(([id, plan]) => {
+            if (plan.basic_per_day !== undefined) SERVICE_RATES[id].basicPerDay = plan.basic_per_day;
+            if (plan.periods !== undefined) RATE_PLANS[id].periods = JSON.parse(JSON.stringify(plan.periods));
+            if (plan.demand_per_kw !== undefined) SERVICE_RATES[id].demandPerKw = plan.demand_per_kw;
+            if (plan.demand_window_minutes !== undefined) SERVICE_RATES[id].demandWindowMinutes = plan.demand_window_minutes;
+            if (plan.last_verified !== undefined) RATE_PLANS[id].lastVerified = plan.last_verified;
+            // R-30 seasons are replaced whole, since their blocks only make sense together
+            Object.entries(plan.energy || {}).forEach(([period, value]) => {
+                ENERGY_RATES[id][period] = typeof value === 'number' ? value : JSON.parse(JSON.stringify(value));
+            });
+        });
+    }
+
+    // Problems with a rates file, as readable strings; unknown keys are reported so typos don't go unnoticed
+    function validateRates(config) {
+        const errors = [];
+        const isRate = v => typeof v === 'number' && isFinite(v) && v >= 0;
+        const isObject = v => v !== null && typeof v === 'object' && !Array.isArray(v);
+        const unknownKeys = (obj, allowed, where) => Object.keys(obj).filter(k => !allowed.includes(k))
+            .forEach(k => errors.push(`unknown key "${k}" in ${where}`));
+
+        if (!isObject(config)) return ['expected a JSON object'];
+        unknownKeys(config, ['fuel', 'windows', 'plans', 'holidays'], 'the file');
+
+        if (config.holidays !== undefined) {
+            if (!isObject(config.holidays)) errors.push('holidays must be an object');
+            else {
+                unknownKeys(config.holidays, ['add', 'remove'], 'holidays');
+                ['add', 'remove'].forEach(k => {
+                    const dates = config.holidays[k];
+                    const isDate = v => typeof v === 'string' && /^\d{4}-\d{2}-\d{2}$/.test(v) && !isNaN(new Date(`${v}T00:00`).getTime());
+                    if (dates !== undefined && !(Array.isArray(dates) && dates.every(isDate))) {
+                        errors.push(`holidays.${k} must list YYYY-MM-DD dates`);
+                    }
+                });
+            }
+        }
+
+        if (config.fuel !== undefined) {
+            if (!isObject(config.fuel)) errors.push('fuel must be an object');
+            else {
+                unknownKeys(config.fuel, ['summer', 'winter'], 'fuel');
+                Object.entries(config.fuel).forEach(([k, v]) => { if (!isRate(v)) errors.push(`fuel.${k} must be a non-negative number`); });
+            }
+        }
+
+        const windowNames = [...Object.keys(TOU_WINDOWS)];
+        if (config.windows !== undefined) {
+            if (!isObject(config.windows)) errors.push('windows must be an object');
+            else {
+                Object.entries(config.windows).forEach(([name, window]) => {
+                    validateWindow(window, `windows.${name}`, errors);
+                    windowNames.push(name);
+                });
+            }
+        }
+
+        if (config.plans === undefined) return errors;
+        if (!isObject(config.plans)) return [...errors, 'plans must be an object'];
+        Object.entries(config.plans).forEach(([id, plan]) => {
+            if (!PLAN_IDS.includes(id)) {
+                errors.push(`unknown plan "${id}" (expected one of ${PLAN_IDS.join(', ')})`);
+                return;
+            }
+            if (!isObject(plan)) {
+                errors.push(`plans.${id} must be an object`);
+                return;
+            }
+            const allowed = ['basic_per_day', 'energy', 'last_verified'];
+            if (SERVICE_RATES[id].demandPerKw !== undefined) allowed.push('demand_per_kw', 'demand_window_minutes');
+            if (id !== 'r30') allowed.push('periods');
+            unknownKeys(plan, allowed, `plans.${id}`);
+            ['basic_per_day', 'demand_per_kw'].forEach(k => {
+                if (plan[k] !== undefined && !isRate(plan[k])) errors.push(`plans.${id}.${k} must be a non-negative number`);
+            });
+            if (plan.demand_window_minutes !== undefined && !(Number.isInteger(plan.demand_window_minutes) && plan.demand_window_minutes > 0)) {
+                errors.push(`plans.${id}.demand_window_minutes must be a whole number of minutes`);
+            }
+            if (plan.last_verified !== undefined && !/^\d{4}-\d{2}-\d{2}$/.test(plan.last_verified)) {
+                errors.push(`plans.${id}.last_verified must be a YYYY-MM-DD date`);
+            }
+            if (plan.periods !== undefined && id !== 'r30') {
+                validatePeriods(plan.periods, Object.keys(ENERGY_RATES[id]), windowNames, `plans.${id}.periods`, errors);
+            }
+            if (plan.energy === undefined) return;
+            if (!isObject(plan.energy)) {
+                errors.push(`plans.${id}.energy must be an object`);
+                return;
+            }
+            unknownKeys(plan.energy, Object.keys(ENERGY_RATES[id]), `plans.${id}.energy`);
+            Object.entries(plan.energy).forEach(([period, value]) => {
+                const where = `plans.${id}.energy.${period}`;
+                if (id !== 'r30') {
+                    if (!isRate(value)) errors.push(`${where} must be a non-negative number`);
+                    return;
+                }
+                if (!isObject(value) || !Array.isArray(value.limits) || !Array.isArray(value.rates)) {
+                    errors.push(`${where} must have limits and rates arrays`);
+                    return;
+                }
+                unknownKeys(value, ['limits', 'rates', 'credit'], where);
+                if (!value.limits.every((limit, i) => isRate(limit) && limit > 0 && (i === 0 || limit > value.limits[i - 1]))) {
+                    errors.push(`${where}.limits must be increasing kWh amounts`);
+                }
+                if (value.rates.length !== value.limits.length + 1 || !value.rates.every(isRate)) {
+                    errors.push(`${where}.rates must have one non-negative rate per block (${value.limits.length + 1})`);
+                }
+                if (value.credit !== undefined && !(isObject(value.credit) && isRate(value.credit.upTo) && isRate(value.credit.rate))) {
+                    errors.push(`${where}.credit must be { upTo, rate } with non-negative numbers`);
+                }
+            });
+        });
+        return errors;
+    }
+
+    function validateWindow(window, where, errors) {
+        const isIntIn = (min, max) => v => Number.isInteger(v) && v >= min && v <= max;
+        if (window === null || typeof window !== 'object' || Array.isArray(window)) {
+            errors.push(`${where} must be an object`);
+            return;
+        }
+        Object.keys(window).filter(k => !['season', 'months', 'weekdays', 'hours', 'holidays'].includes(k))
+            .forEach(k => errors.push(`unknown key "${k}" in ${where}`));
+        const { season, months, weekdays, hours, holidays } = window;
+        const isMonthDay = v => typeof v === 'string' && /^\d{2}-\d{2}$/.test(v);
+        if (season !== undefined && season !== 'summer' && !(season && isMonthDay(season.from) && isMonthDay(season.to))) {
+            errors.push(`${where}.season must be "summer" or { from: "MM-DD", to: "MM-DD" }`);
+        }
+        if (months !== undefined && !(Array.isArray(months) && months.length > 0 && months.every(isIntIn(1, 12)))) {
+            errors.push(`${where}.months must list month numbers 1-12`);
+        }
+        if (weekdays !== undefined && !(Array.isArray(weekdays) && weekdays.length > 0 && weekdays.every(isIntIn(0, 6)))) {
+            errors.push(`${where}.weekdays must list day numbers 0 (Sunday) to 6`);
+        }
+        if (hours !== undefined && !(Array.isArray(hours) && hours.length === 2 && hours.every(isIntIn(0, 24)) && hours[0] !== hours[1])) {
+            errors.push(`${where}.hours must be [start, end] hours from 0 to 24`);
+        }
+        if (holidays !== undefined && holidays !== false) {
+            errors.push(`${where}.holidays can only be false`);
+        }
+    }
+
+    // A periods list needs a window on every entry but the last, which catches the remaining hours
+    function validatePeriods(periods, priced, windowNames, where, errors) {
+        if (!Array.isArray(periods) || periods.length === 0) {
+            errors.push(`${where} must be a non-empty list`);
+            return;
+        }
+        periods.forEach((p, i) => {
+            const last = i === periods.length - 1;
+            if (!p || !priced.includes(p.period)) {
+                errors.push(`${where}[${i}].period must be one of ${priced.join(', ')}`);
+            } else if (last && p.window !== undefined) {
+                errors.push(`${where}[${i}] is the last period and catches all remaining hours, so it takes no window`);
+            } else if (!last && !windowNames.includes(p.window)) {
+                errors.push(`${where}[${i}].window must name a window (${windowNames.join(', ')})`);
+            }
+        });
+    }
+
+    const RateBilling = {
+        FUEL_RATES, FCR_SEASON, FEE_LINES, TAX_RATE, SERVICE_RATES, ENERGY_RATES, RATE_PLANS, PLAN_IDS, BLOCK_KEYS, MAX_PLAUSIBLE_KW,
+        TOU_WINDOWS, HOLIDAY_RULES, HOLIDAYS, observedHolidays, PLAN_MODELS, registerPlan, inSeason, inWindow, scheduleFor, classifyByPeriods, classifyHour, isOnPeak, getTouOaPeriod,
+        aggregateMonthly, parseBillAdjustments, adjustmentsFor, billMonth, priceBlocks, roundCents, finalizeBill, sumBills, calculateBills,
+        describeWindow, explainBill,
+        findPlanId, parseEnrollmentHistory, planOnDate, billEnrollmentHistory, checkTermCommitment,
+        costDrivers, checkDemandAssumptions, MIN_SUMMER_DAYS, checkSummerCoverage,
+        readingMinutes, detectIntervalMinutes, findGaps, GAP_FILL_METHODS, fillGaps,
+        detectIntervalLabel, toIntervalStart, median,
+        daysInMonth, addYears, daysBetween, fullYearsBetween,
+        GEORGIA_TIME_ZONE, wallClockInstant, clockChange, georgiaClock, toGeorgiaTime,
+        hourlyPrice, plan,
+        exportRates, loadRates, resetRates, defaultRates, withRates, diffRates,
+        detectDelimiter, findNamedColumns, parseUsageCsv, parseUsageJson, DEDUPE_POLICIES, mergeUsage, ANONYMIZED_HEADER, ANONYMIZED_YEAR, anonymizeUsage, restoreAnonymized, build8760,
+        DISPATCH_YEAR, dispatchSeries, readReoptResults, PIPELINE_STEPS, validatePipeline, applyPipeline
+    };
+
+    if (typeof module !== 'undefined' && module.exports) {
+        module.exports = RateBilling;
+    } else {
+        root.RateBilling = RateBilling;
+    }
+})(typeof window !== 'undefined' ? window : globalThis);
@@ -0,0 +1,89 @@
+// TOU schedule evaluation on its own: which period a moment falls in and what energy costs then.
+// No dependencies and no rate tables of its own, so a thermostat script, a charger controller or
+// firmware can carry just this file and a schedule. Loads as window.RateSchedule in the page
+// (before billing.js, which uses it) and in Node with require('./schedule.js').
+//
+// A schedule is plain data, the shape RateBilling.scheduleFor(planId) returns:
+//   summer   - the plan's summer season, { from: 'MM-DD', to: 'MM-DD' } (see inSeason)
+//   windows  - { name: window } (see inWindow)
+//   holidays - 'YYYY-MM-DD' dates that windows with holidays: false skip
+//   periods  - [{ period, window }, ..., { period }]: the first matching window wins and
+//              the last entry, which has no window, takes every other hour
+//   energy   - $/kWh per period, or R-30 style blocks per season:
+//              { summer: { limits, rates, credit? }, winter: { ... } }
+//
+// Example:
+//   const schedule = JSON.parse(fs.readFileSync('tou-reo-schedule.json', 'utf8'));
+//   RateSchedule.rateAt(schedule, new Date(2024, 6, 1, 15)); // { period: 'on_peak', rate: 0.297868 }
+(function (root) {
+    'use strict';
+
+    // Whether a date falls in an inclusive { from: 'MM-DD', to: 'MM-DD' } season;
+    // a range whose end comes before its start wraps over the new year
+    function inSeason(season, dt) {
+        const md = (dt.getMonth() + 1) * 100 + dt.getDate();
+        const [from, to] = [season.from, season.to].map(v => Number(v.replace('-', '')));
+        return from <= to ? md >= from && md <= to : md >= from || md <= to;
+    }
+
+    // Whether dt falls in a time window. Every condition a window sets must hold:
+    //   season   - 'summer' for the schedule's summer, or an inline { from, to } season
+    //   months   - month numbers, 1-12
+    //   weekdays - getDay() numbers, 0 = Sunday
+    //   hours    - [start, end) hours of the day; an end before the start wraps past midnight
+    //   holidays - false to leave out the holiday dates
+    function inWindow(window, dt, { summer, holidays = [] } = {}) {
+        if (window.season) {
+            const season = window.season === 'summer' ? summer : window.season;
+            if (!inSeason(season, dt)) return false;
+        }
+        if (window.months && !window.months.includes(dt.getMonth() + 1)) return false;
+        if (window.weekdays && !window.weekdays.includes(dt.getDay())) return false;
+        if (window.hours) {
+            const [start, end] = window.hours;
+            const hour = dt.getHours();
+            const inHours = start < end ? hour >= start && hour < end : hour >= start || hour < end;
+            if (!inHours) return false;
+        }
+        if (window.holidays === false && holidays.includes(localDateKey(dt))) return false;
+        return true;
+    }
+
+    function localDateKey(dt) {
+        return `${dt.getFullYear()}-${String(dt.getMonth() + 1).padStart(2, '0')}-${String(dt.getDate()).padStart(2, '0')}`;
+    }
+
+    // The schedule period dt falls in
+    function periodAt(schedule, dt) {
+        const { periods, windows } = schedule;
+        const match = periods.find(p => p.window && inWindow(windows[p.window], dt, schedule));
+        return (match || periods[periods.length - 1]).period;
+    }
+
+    // { period, rate } at dt: the energy rate ($/kWh, before fuel recovery, riders and taxes)
+    // for the next kWh. Block-priced schedules need the month's kWh so far to find the block;
+    // their period is the block, e.g. 'summer_tier2'.
+    function rateAt(schedule, dt, monthKwhBefore = 0) {
+        const { energy } = schedule;
+        if (!(energy.summer && energy.summer.rates)) {
+            const period = periodAt(schedule, dt);
+            return { period, rate: energy[period] };
+        }
+        const isSummer = inSeason(schedule.summer, dt);
+        const blocks = isSummer ? energy.summer : energy.winter;
+        const tier = blocks.limits.filter(limit => monthKwhBefore >= limit).length;
+        let rate = blocks.rates[tier];
+        if (blocks.credit && monthKwhBefore < blocks.credit.upTo) {
+            rate -= blocks.credit.rate;
+        }
+        return { period: isSummer ? `summer_tier${tier + 1}` : 'winter', rate };
+    }
+
+    const RateSchedule = { inSeason, inWindow, localDateKey, periodAt, rateAt };
+
+    if (typeof module !== 'undefined' && module.exports) {
+        module.exports = RateSchedule;
+    } else {
+        root.RateSchedule = RateSchedule;
+    }
+})(typeof window !== 'undefined' ? window : globalThis);
@@ -3,6 +3,71 @@ document.addEventListener('DOMContentLoaded', () => {
     const fileInput = document.getElementById('file-input');
     const errorMessage = document.getElementById('error-message');
     const resultsSection = document.getElementById('results-section');
+    const validationSection = document.getElementById('validation-section');
+
+    // Tariff data and bill math come from billing.js; this file is the page around it
+    const {
+        FUEL_RATES, FCR_SEASON, FEE_LINES, TAX_RATE, SERVICE_RATES, ENERGY_RATES, RATE_PLANS, PLAN_IDS, BLOCK_KEYS, HOLIDAYS,
+        inSeason, classifyHour, findPlanId, parseBillAdjustments, aggregateMonthly, billMonth, roundCents, calculateBills,
+        parseEnrollmentHistory, billEnrollmentHistory, checkTermCommitment,
+        costDrivers, checkDemandAssumptions, MIN_SUMMER_DAYS, checkSummerCoverage,
+        readingMinutes, detectIntervalMinutes, findGaps, GAP_FILL_METHODS, fillGaps,
+        detectIntervalLabel, toIntervalStart, median, hourlyPrice,
+        daysInMonth, addYears, daysBetween, fullYearsBetween, GEORGIA_TIME_ZONE, wallClockInstant, clockChange, toGeorgiaTime,
+        exportRates, loadRates, resetRates, defaultRates, withRates, diffRates, explainBill,
+        DEDUPE_POLICIES, mergeUsage, parseUsageJson, applyPipeline, detectDelimiter, findNamedColumns,
+        ANONYMIZED_HEADER, ANONYMIZED_YEAR, anonymizeUsage, restoreAnonymized, build8760, dispatchSeries, readReoptResults
+    } = RateBilling;
+
+    // Hypothetical loads stacked on the uploaded profile. Each profile()
+    // returns the kWh the add-on draws in each hour of the day (24 values).
+    const LOAD_ADDONS = [
+        {
+            key: 'ev',
+            inputs: ['addon-ev-kwh', 'addon-ev-start', 'addon-ev-kw'],
+            describe: ([kwh, start]) => `EV (${kwh} kWh/night from ${String(start).padStart(2, '0')}:00)`,
+            profile: ([kwh, start, kw]) => {
+                // Charge at full rate from the start hour until the night's energy is delivered
+                const hours = new Array(24).fill(0);
+                let remaining = kwh;
+                for (let h = 0; h < 24 && remaining > 0 && kw > 0; h++) {
+                    const draw = Math.min(kw, remaining);
+                    hours[(Math.floor(start) + h) % 24] += draw;
+                    remaining -= draw;
+                }
+                return hours;
+            }
+        },
+        {
+            key: 'hottub',
+            inputs: ['addon-hottub-kwh'],
+            describe: ([kwh]) => `hot tub (${kwh} kWh/day)`,
+            profile: ([kwh]) => new Array(24).fill(kwh / 24)
+        },
+        {
+            key: 'constant',
+            inputs: ['addon-constant-watts'],
+            describe: ([watts]) => `constant load (${watts} W)`,
+            profile: ([watts]) => new Array(24).fill(watts / 1000)
+        }
+    ];
+
+    const LOAD_ADDON_INPUTS = LOAD_ADDONS.flatMap(a => [`addon-${a.key}`, ...a.inputs]);
+
+    // Raw sheet rows from the last upload, re-analyzed when options change
+    let lastRows = null;
+    // The files behind them, re-read when an option changes how they are read
+    let lastFiles = null;
+    // Which export the rows came from ({ label, notes }), see adaptRows
+    let lastSource = null;
+    // Records from the most recent analysis, kept for exports
+    let lastRecords = [];
+    // Monthly aggregates from the most recent analysis; single months are billed from these on demand
+    let lastMonthlyUsage = {};
+    // Per-month data-quality labels for the same analysis (see rateMonthConfidence)
+    let monthConfidence = {};
+    // Bill adjustments from the options panel (see parseBillAdjustments), applied by billMonth
+    let billAdjustments = [];
 
     // Drag & Drop handlers
     dropZone.addEventListener('dragover', (e) => {
@@ -19,7 +84,7 @@ document.addEventListener('DOMContentLoaded', () => {
         dropZone.classList.remove('drag-over');
         const files = e.dataTransfer.files;
         if (files.length > 0) {
-            handleFile(files[0]);
+            handleFiles(files);
         }
     });
 
@@ -29,396 +94,2229 @@ document.addEventListener('DOMContentLoaded', () => {
 
     fileInput.addEventListener('change', (e) => {
         if (e.target.files.length > 0) {
-            handleFile(e.target.files[0]);
+            handleFiles(e.target.files);
+        }
+    });
+
+    // Option handlers
+    ['range-start', 'range-end', 'dry-run', 'show-skipped', ...PLAN_IDS.map(id => `compare-${id}`), 'time-offset', 'timestamp-format', 'time-zone', 'timestamp-column', 'kwh-column', 'interval-label', 'gap-fill', 'repair-dates', 'meter-cutover', 'heating-cop', 'flatbill-premium', 'home-sqft', 'home-occupants', 'stale-months', 'min-summer-days', 'enrollment-history', 'bill-adjustments', 'significant-dates', 'billed-usage', 'billed-tolerance', 'stress-days', 'stress-factor', 'stability-samples', ...LOAD_ADDON_INPUTS].forEach(id => {
+        document.getElementById(id).addEventListener('change', runAnalysis);
+    });
+    // The delimiter and the duplicate policy are applied while reading, so the files are read again
+    ['csv-delimiter', 'dedupe-policy'].forEach(id => document.getElementById(id).addEventListener('change', () => {
+        if (lastFiles) handleFiles(lastFiles);
+    }));
+
+    renderPlanGlossary();
+    applyQueryParams();
+
+    document.getElementById('detail-month').addEventListener('change', (e) => renderMonthDetail(e.target.value));
+    document.getElementById('anatomy-plan').addEventListener('change', () => renderMonthDetail(document.getElementById('detail-month').value));
+
+    // Rates file: replaces the built-in prices until it is cleared or the page is reloaded
+    const ratesStatus = document.getElementById('rates-status');
+    const builtInRatesNote = ratesStatus.textContent;
+    let loadedRates = null; // { name, config } while a rates file is in use
+    let lastSwitchSummary = null; // text of the Plan Change Summary, when one is shown
+    document.getElementById('rates-file').addEventListener('change', (e) => {
+        const file = e.target.files[0];
+        if (!file) {
+            resetRates();
+            loadedRates = null;
+            ratesStatus.textContent = builtInRatesNote;
+            runAnalysis();
+            return;
         }
+        const reader = new FileReader();
+        reader.onload = (ev) => {
+            try {
+                const config = JSON.parse(ev.target.result);
+                loadRates(config);
+                loadedRates = { name: file.name, config };
+                ratesStatus.textContent = `Using rates from ${file.name}; anything it leaves out keeps the built-in price.`;
+                runAnalysis();
+            } catch (err) {
+                showError(`Could not load ${file.name}: ${err.message}`);
+            }
+        };
+        reader.readAsText(file);
+    });
+    // Pipeline file: preprocessing steps run on every upload until it is cleared
+    const pipelineStatus = document.getElementById('pipeline-status');
+    const noPipelineNote = pipelineStatus.textContent;
+    let loadedPipeline = null; // { name, config } while a pipeline file is in use
+    document.getElementById('pipeline-file').addEventListener('change', (e) => {
+        const file = e.target.files[0];
+        if (!file) {
+            loadedPipeline = null;
+            pipelineStatus.textContent = noPipelineNote;
+            runAnalysis();
+            return;
+        }
+        const reader = new FileReader();
+        reader.onload = (ev) => {
+            try {
+                const config = JSON.parse(ev.target.result);
+                applyPipeline([], config); // validates
+                loadedPipeline = { name: file.name, config };
+                pipelineStatus.textContent = `Running ${config.steps.length} step(s) from ${file.name}: ${config.steps.map(s => s.step).join(' > ')}.`;
+                runAnalysis();
+            } catch (err) {
+                showError(`Could not load ${file.name}: ${err.message}`);
+            }
+        };
+        reader.readAsText(file);
+    });
+
+    document.getElementById('rates-download').addEventListener('click', () => {
+        downloadFile('rates.json', JSON.stringify(exportRates(), null, 2), 'application/json');
     });
 
-    function handleFile(file) {
-        const isExcel = file.name.endsWith('.xlsx') || file.name.endsWith('.xls');
+    document.getElementById('switch-copy').addEventListener('click', () => {
+        if (lastSwitchSummary) navigator.clipboard.writeText(lastSwitchSummary);
+    });
+    document.getElementById('switch-download').addEventListener('click', () => {
+        if (lastSwitchSummary) downloadFile('plan-change-summary.txt', lastSwitchSummary, 'text/plain');
+    });
+
+    // Export handlers
+    document.getElementById('export-csv').addEventListener('click', () => exportPriceCurve('csv'));
+    document.getElementById('export-json').addEventListener('click', () => exportPriceCurve('json'));
+    document.getElementById('export-anonymized').addEventListener('click', () => {
+        if (lastRecords.length > 0) downloadFile('usage_profile_anonymized.csv', anonymizeUsage(lastRecords), 'text/csv');
+    });
+    document.getElementById('export-8760').addEventListener('click', () => {
+        if (lastRecords.length === 0) return;
+        const { hours, filled } = build8760(lastRecords);
+        const pad = n => String(n).padStart(2, '0');
+        const lines = hours.map(({ month, day, hour, kwh, filled }, i) =>
+            `${i + 1},${pad(month)}-${pad(day)} ${pad(hour)}:00,${kwh.toFixed(4)},${filled ? 1 : 0}`);
+        downloadFile('annual_load_8760.csv', ['hour,timestamp,kwh,filled', ...lines].join('\n'), 'text/csv');
+        document.getElementById('export-8760-status').textContent = filled
+            ? `${filled} of 8760 hours had no reading or hold readings filled in for gaps, and are marked filled = 1.`
+            : 'Every hour came from a reading.';
+    });
 
-        if (!isExcel) {
-            showError('Please upload a valid Excel file (.xlsx or .xls).');
+    function handleFiles(fileList) {
+        const files = Array.from(fileList);
+        const unsupported = files.find(f => !/\.(xlsx|xls|csv|tsv|xml|jsonl?|zip)(\.(gz|zst))?$/i.test(f.name));
+        if (unsupported) {
+            showError('Please upload a GA Power Excel file (.xlsx or .xls), a supported CSV or XML export, JSON records, or a ZIP of them (gzip-compressed is fine too).');
             return;
         }
 
-        const reader = new FileReader();
+        lastFiles = files;
+        Promise.all(files.map(readFileSheets))
+            .then(groups => {
+                const sheets = groups.flatMap((members, i) => /\.zip(\.(gz|zst))?$/i.test(files[i].name)
+                    ? members.map(m => ({ name: `${files[i].name}/${m.name}`, rows: m.rows }))
+                    : members);
+                const adapted = mergeSheets(sheets);
+                if (!adapted) return;
+                if (!adapted[0].adapter) {
+                    lastRows = adapted[0].rows;
+                    lastSource = { label: 'Georgia Power export', notes: adapted[0].notes, circuits: [], files: adapted[0].files };
+                } else {
+                    lastRows = toHourlyRows(adapted.flatMap(a => a.intervals));
+                    lastSource = {
+                        label: adapted[0].adapter.label,
+                        notes: [...(adapted.length > 1 ? [`${adapted.length} files`] : []), ...adapted.flatMap(a => a.notes)],
+                        circuits: mergeCircuits(adapted.flatMap(a => a.circuits || [])),
+                        files: adapted.reduce((n, a) => n + a.files, 0),
+                        overlap: adapted[0].overlap,
+                        duplicates: adapted.reduce((n, a) => n + (a.overlap ? a.overlap.count : 0), 0)
+                    };
+                }
+                runAnalysis();
+            })
+            .catch(err => {
+                showError('Error processing file: ' + err.message);
+                console.error(err);
+            });
+    }
+
+    // [{ name, rows }] for a dropped file: one entry, or one per usage file inside a ZIP
+    function readFileSheets(file) {
+        if (/\.xml$/i.test(file.name)) {
+            return readFileText(file).then(text => [{ name: file.name, rows: xmlToRows(text) }]);
+        }
+        return readFileBytes(file).then(bytes => sheetsFromBytes(file.name, bytes));
+    }
+
+    function readFileText(file) {
+        return new Promise((resolve, reject) => {
+            const reader = new FileReader();
+            reader.onload = (e) => resolve(e.target.result);
+            reader.onerror = () => reject(reader.error);
+            reader.readAsText(file);
+        });
+    }
+
+    function readFileBytes(file) {
+        return new Promise((resolve, reject) => {
+            const reader = new FileReader();
+            reader.onload = (e) => resolve(new Uint8Array(e.target.result));
+            reader.onerror = () => reject(reader.error);
+            reader.readAsArrayBuffer(file);
+        });
+    }
+
+    // Compressed files (by extension or their first bytes) are unpacked and read as the file
+    // inside, so usage.csv.gz reads as usage.csv and export.zip.gz as export.zip
+    function sheetsFromBytes(name, bytes) {
+        const compression = compressionOf(name, bytes);
+        if (compression) {
+            return decompress(name, bytes, compression).then(inner => sheetsFromBytes(name.replace(/\.(gz|zst)$/i, ''), inner));
+        }
+        if (/\.zip$/i.test(name)) return Promise.resolve(zipSheets(name, bytes));
+        return Promise.resolve([{ name, rows: sheetFromBytes(name, bytes) }]);
+    }
+
+    function compressionOf(name, bytes) {
+        if (/\.gz$/i.test(name) || (bytes[0] === 0x1f && bytes[1] === 0x8b)) return 'gzip';
+        if (/\.zst$/i.test(name) || (bytes[0] === 0x28 && bytes[1] === 0xb5 && bytes[2] === 0x2f && bytes[3] === 0xfd)) return 'zstd';
+        return null;
+    }
+
+    // Unpacked in the browser with DecompressionStream. Every current browser handles gzip;
+    // Zstandard only works where the browser supports it.
+    function decompress(name, bytes, format) {
+        let stream;
+        try {
+            stream = new DecompressionStream(format);
+        } catch (err) {
+            return Promise.reject(new Error(format === 'zstd'
+                ? `${name} is Zstandard-compressed, which this browser can't open. Decompress it first (zstd -d ${name}) or compress it with gzip instead.`
+                : `${name} is gzip-compressed, which this browser can't open. Decompress it first (gunzip ${name}).`));
+        }
+        return new Response(new Blob([bytes]).stream().pipeThrough(stream)).arrayBuffer()
+            .then(buffer => new Uint8Array(buffer), () => {
+                throw new Error(`${name} could not be decompressed; the file may be damaged or not really ${format === 'zstd' ? 'Zstandard' : 'gzip'}.`);
+            });
+    }
+
+    function zipSheets(name, bytes) {
+        const archive = XLSX.CFB.read(bytes, { type: 'array' });
+        const sheets = [];
+        archive.FileIndex.forEach((entry, i) => {
+            const path = archive.FullPaths[i];
+            // Skip folders and the resource-fork copies macOS adds when zipping
+            if (entry.type !== 2 || /(^|\/)__MACOSX\//.test(path) || !/\.(xlsx|xls|csv|tsv|xml|jsonl?)$/i.test(entry.name)) return;
+            const content = entry.content instanceof Uint8Array ? entry.content : new Uint8Array(entry.content);
+            sheets.push({ name: entry.name, rows: sheetFromBytes(entry.name, content) });
+        });
+        if (sheets.length === 0) throw new Error(`${name} has no Excel, CSV, XML or JSON files in it.`);
+        return sheets;
+    }
+
+    // First sheet of an Excel or CSV file (or an eGauge or Green Button XML export) as an array of row arrays
+    function sheetFromBytes(name, data) {
+        if (/\.xml$/i.test(name)) return xmlToRows(new TextDecoder().decode(data));
+        if (/\.jsonl?$/i.test(name)) return jsonToRows(new TextDecoder().decode(data));
+        // raw keeps CSV cells as text so adapters see timestamps as written. The separator is
+        // detected here because SheetJS only looks at the start of the file, which in many
+        // exports is a disclaimer full of commas.
+        const delimited = /\.(csv|tsv)$/i.test(name);
+        const delimiter = delimited ? (document.getElementById('csv-delimiter').value || detectDelimiter(new TextDecoder().decode(data))) : null;
+        const workbook = XLSX.read(data, { type: 'array', raw: delimited, ...(delimiter ? { FS: delimiter === 'tab' ? '\t' : delimiter } : {}) });
+        const worksheet = workbook.Sheets[workbook.SheetNames[0]];
+        return XLSX.utils.sheet_to_json(worksheet, { header: 1 }); // Array of arrays
+    }
+
+    function xmlToRows(text) {
+        return /<(\w+:)?IntervalBlock\b/.test(text) ? greenButtonXmlToRows(text) : egaugeXmlToRows(text);
+    }
+
+    // Adapted entries for the dropped usage files (ZIPs already unpacked), or null after
+    // showing why they can't be combined. Monthly downloads split a history over several
+    // files that often overlap, so files from the same source are merged into one entry
+    // and a repeated timestamp is settled by the duplicate policy under Analysis Options, as
+    // is one repeated within a file. Emporia files stay separate, one per device. files counts
+    // the files behind each entry.
+    function mergeSheets(sheets) {
+        const adapted = sheets.map(s => {
+            const daily = pivotDailyRows(s.rows);
+            const rows = daily ? daily.rows : s.rows;
+            return { name: s.name, rows, notes: daily ? [daily.note] : [], files: 1, ...adaptRows(rows) };
+        });
+        const describe = a => `${a.name}: ${a.adapter ? a.adapter.label : 'Georgia Power export'}`;
+        if (new Set(adapted.map(a => a.adapter ? a.adapter.key : 'ga')).size > 1) {
+            showError('Files from different sources can\'t be combined. Upload the files from one source at a time.', adapted.map(describe));
+            return null;
+        }
+        const policy = document.getElementById('dedupe-policy').value || 'first';
+        if (adapted.length === 1 || (adapted[0].adapter && adapted[0].adapter.multiFile)) {
+            return adapted.map(a => {
+                if (!a.adapter) return a;
+                const { series, overlap } = mergeUsage([a.intervals], { policy });
+                return { ...a, intervals: series, overlap };
+            });
+        }
+
+        const notes = [`${adapted.length} files combined`];
+        if (!adapted[0].adapter) {
+            // processData drops the repeated hours and flags copies that disagree
+            const rows = adapted[0].rows.concat(...adapted.slice(1).map(a => rowsAfterHeader(a.rows)));
+            return [{ name: adapted[0].name, rows, notes: [...notes, ...adapted.flatMap(a => a.notes.map(note => `${a.name}: ${note}`))], files: adapted.length }];
+        }
+        const { series, overlap } = mergeUsage(adapted.map(a => a.intervals), { policy });
+        return [{
+            name: adapted[0].name,
+            adapter: adapted[0].adapter,
+            intervals: series,
+            notes: [...notes, ...adapted.flatMap(a => a.notes.map(note => `${a.name}: ${note}`))],
+            circuits: adapted.flatMap(a => a.circuits || []),
+            files: adapted.length,
+            overlap
+        }];
+    }
+
+    // Hour of the day (0-23) an hourly column header stands for: "12:00 AM", "1 PM", "13:00",
+    // "HE05" or "Hour 5" (hour ending, so 4), or a bare number. Bare numbers are hour starting
+    // when the list has a 0 and hour ending when it runs 1-24, so they are resolved by the caller;
+    // they come back as { number }.
+    function hourColumn(cell) {
+        const text = String(cell ?? '').trim();
+        let m = text.match(/^(\d{1,2})(?::00)?\s*([AP])\.?M\.?$/i);
+        if (m && Number(m[1]) >= 1 && Number(m[1]) <= 12) return (Number(m[1]) % 12) + (m[2].toUpperCase() === 'P' ? 12 : 0);
+        m = text.match(/^(\d{1,2}):00$/);
+        if (m && Number(m[1]) <= 23) return Number(m[1]);
+        m = text.match(/^(?:he|hour ending|hour|hr)\s*(\d{1,2})$/i);
+        if (m && Number(m[1]) >= 1 && Number(m[1]) <= 24) return Number(m[1]) - 1;
+        m = text.match(/^\d{1,2}$/);
+        return m && Number(text) <= 24 ? { number: Number(text) } : null;
+    }
+
+    // Daily exports with one row per day and a column per hour, pivoted into the GA Power
+    // layout ("Hour" / "kWh", one row per hour). Returns { rows, note }, or null when no row
+    // has a date column and 24 hour columns.
+    function pivotDailyRows(rows) {
+        for (let i = 0; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row || row.length < 25) continue;
+            const dateIdx = row.findIndex(c => /date|day/i.test(String(c ?? '')));
+            if (dateIdx === -1) continue;
+            const columns = row.map((c, idx) => (idx === dateIdx ? null : hourColumn(c)));
+            const numbers = columns.filter(c => c && typeof c === 'object').map(c => c.number);
+            const shift = numbers.includes(0) ? 0 : 1;
+            const hours = columns.map(c => (c && typeof c === 'object' ? c.number - shift : c));
+            const distinct = new Set(hours.filter(h => h !== null && h >= 0 && h <= 23));
+            if (distinct.size < 24) continue;
+
+            const timestampFormat = document.getElementById('timestamp-format').value;
+            const pad = n => String(n).padStart(2, '0');
+            const out = [['Hour', 'kWh']];
+            let days = 0;
+            rows.slice(i + 1).forEach(r => {
+                const cell = r && r[dateIdx];
+                let date = null;
+                if (typeof cell === 'number') {
+                    const d = new Date(Math.round((cell - 25569) * 1440) * 60 * 1000);
+                    date = new Date(d.getUTCFullYear(), d.getUTCMonth(), d.getUTCDate());
+                } else if (cell) {
+                    const parsed = parseTimestampText(`${String(cell).trim().split(/[ T]/)[0]} 00:00`, timestampFormat);
+                    date = parsed && parsed.dt;
+                }
+                if (!date) return;
+                days++;
+                const day = `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
+                hours.forEach((hour, idx) => {
+                    const value = r[idx];
+                    if (hour === null || hour < 0 || hour > 23 || value === undefined || value === null || value === '') return;
+                    out.push([`${day} ${pad(hour)}:00`, value]);
+                });
+            });
+            return { rows: out, note: `One row per day with ${distinct.size} hour columns: ${days} day(s) turned into hourly readings` };
+        }
+        return null;
+    }
+
+    // Data rows of a GA Power export, below its header
+    function rowsAfterHeader(rows) {
+        const header = findUsageHeader(rows);
+        return header ? rows.slice(header.index + 1) : rows;
+    }
+
+    // --- Source adapters ---
+    // Exports from other meters are converted into the GA Power layout
+    // (an "Hour" / "kWh" header with one row per hour) before processData runs.
+    // detect() gets the lowercased header cells; convert() returns
+    // { intervals: [{ dt, kwh }], notes: [], circuits?: [{ name, kwh }] }.
+    // multiFile adapters accept several files at once (one per device).
+    const SOURCE_ADAPTERS = [
+        {
+            key: 'home-assistant-statistics',
+            label: 'Home Assistant long-term statistics',
+            detect: header => header.includes('start') && header.includes('sum'),
+            convert: (rows, headerIndex) => convertHomeAssistant(rows, headerIndex, 'start', 'sum', 'current')
+        },
+        {
+            key: 'home-assistant-history',
+            label: 'Home Assistant history',
+            detect: header => header.includes('entity_id') && header.includes('state') && header.includes('last_changed'),
+            convert: (rows, headerIndex) => convertHomeAssistant(rows, headerIndex, 'last_changed', 'state', 'previous')
+        },
+        {
+            key: 'sense',
+            label: 'Sense export',
+            detect: header => header.includes('datetime') && header.includes('device id') && header.includes('kwh'),
+            convert: convertSense
+        },
+        {
+            key: 'emporia',
+            label: 'Emporia Vue export',
+            detect: header => header.some(c => c.startsWith('time bucket')) && header.some(c => c.includes('(kwhs)')),
+            convert: convertEmporia,
+            multiFile: true
+        },
+        {
+            key: 'green-button',
+            label: 'Green Button data',
+            detect: header => header[0] === 'green button start',
+            convert: convertGreenButton
+        },
+        {
+            key: 'json',
+            label: 'JSON records',
+            detect: header => header[0] === 'json timestamp',
+            convert: (rows, headerIndex) => ({
+                intervals: rows.slice(headerIndex + 1).map(([time, kwh, zoned]) => ({ dt: new Date(time), kwh: Math.max(0, kwh), ...(zoned ? { zoned: true } : {}) })),
+                notes: []
+            })
+        },
+        {
+            key: 'anonymized',
+            label: 'Anonymized profile',
+            detect: header => header.join(',') === ANONYMIZED_HEADER.join(',').toLowerCase(),
+            convert: (rows, headerIndex) => ({
+                intervals: restoreAnonymized(rows.slice(headerIndex + 1)),
+                notes: [`Dates are a stand-in ${ANONYMIZED_YEAR} calendar, so season and holiday edges may be up to six days off, and kWh was rescaled before sharing`]
+            })
+        },
+        {
+            key: 'sam',
+            label: 'SAM hourly results',
+            detect: header => header.some(c => /from grid \| \(kw\)$/.test(c)),
+            convert: convertSam
+        },
+        {
+            key: 'reopt',
+            label: 'REopt results',
+            detect: header => header[0] === 'reopt timestep',
+            convert: (rows, headerIndex) => {
+                const data = rows.slice(headerIndex + 1);
+                const load = data.every(r => r[2] !== '' && r[2] !== undefined) ? data.map(r => r[2]) : null;
+                return convertDispatch(data.map(r => r[1]), load, Number(data[0] && data[0][3]) || null);
+            }
+        },
+        {
+            key: 'egauge',
+            label: 'eGauge export',
+            detect: header => header.includes('date & time') && header.some(c => c.endsWith('[kwh]')),
+            convert: convertEgauge
+        }
+    ];
+
+    // { adapter, intervals, notes, circuits }; adapter is null for a GA Power export
+    function adaptRows(rows) {
+        // Skip leading "#" comment lines (Sense puts a detail link there)
+        const headerIndex = rows.findIndex((r, i) => i < 20 && r && r.length > 0 && !String(r[0] ?? '').startsWith('#'));
+        const header = headerIndex === -1 ? [] : rows[headerIndex].map(c => String(c ?? '').trim().toLowerCase());
+        const adapter = SOURCE_ADAPTERS.find(a => a.detect(header));
+        if (!adapter) return { adapter: null };
+        return { adapter, ...adapter.convert(rows, headerIndex) };
+    }
+
+    // Same-named circuits from several files are added together, largest first
+    function mergeCircuits(circuits) {
+        const totals = new Map();
+        circuits.forEach(c => totals.set(c.name, (totals.get(c.name) || 0) + c.kwh));
+        return [...totals.entries()].map(([name, kwh]) => ({ name, kwh })).sort((a, b) => b.kwh - a.kwh);
+    }
+
+    // Sum intervals into clock hours and lay them out like a GA Power export. Intervals marked
+    // zoned are exact instants and are written as UTC times, so processData moves them to the
+    // Georgia clock once, whatever zone the browser or the time zone option is in; the second
+    // pass through the repeated November hour gets a row of its own.
+    function toHourlyRows(intervals) {
+        const hours = new Map(); // "time zoned repeated" -> kWh
+        intervals.forEach(({ dt, kwh, zoned, repeated }) => {
+            const hour = zoned
+                ? Math.floor(dt.getTime() / 3600000) * 3600000
+                : new Date(dt.getFullYear(), dt.getMonth(), dt.getDate(), dt.getHours()).getTime();
+            const key = `${hour} ${zoned ? 1 : 0} ${!zoned && repeated ? 1 : 0}`;
+            hours.set(key, (hours.get(key) || 0) + kwh);
+        });
+        const pad = n => String(n).padStart(2, '0');
+        const rows = [['Hour', 'kWh']];
+        [...hours.keys()].map(key => key.split(' ').map(Number)).sort((a, b) => a[0] - b[0] || a[2] - b[2]).forEach(([t, zoned, repeated]) => {
+            const d = new Date(t);
+            const time = zoned ? d.toISOString().replace(/\.000Z$/, 'Z') : `${d.getFullYear()}-${pad(d.getMonth() + 1)}-${pad(d.getDate())} ${pad(d.getHours())}:00`;
+            rows.push([time, Number(hours.get(`${t} ${zoned} ${repeated}`).toFixed(4))]);
+        });
+        return rows;
+    }
+
+    // ISO 8601 (UTC or with offset), "YYYY-MM-DD HH:MM[:SS]" local, or epoch seconds, as
+    // { dt, zoned } or null; zoned is set for epoch seconds and a Z or offset, which name an
+    // exact instant rather than a clock time
+    function parseIsoStamp(value) {
+        const text = String(value ?? '').trim();
+        if (/^\d{9,10}(\.\d+)?$/.test(text)) return { dt: new Date(parseFloat(text) * 1000), zoned: true };
+        const dt = new Date(text.replace(' ', 'T'));
+        return isNaN(dt.getTime()) ? null : { dt, zoned: /(?:Z|[+-]\d{2}:?\d{2})$/i.test(text) };
+    }
+
+    // Cumulative meter readings -> interval kWh. When a reading drops below the one
+    // before it, a drop to near zero is a counter reset (the new value is the usage since
+    // the reset); a smaller drop is a net register running backwards while exporting,
+    // which bills as no usage. stamp picks whether each delta belongs to the previous
+    // reading's time (a meter read at that instant) or the current one (a row that
+    // already names its interval start).
+    function registerDeltas(readings, stamp) {
+        const sorted = readings.slice().sort((a, b) => a.dt - b.dt);
+        const intervals = [];
+        let resets = 0;
+        let reversals = 0;
+        for (let i = 1; i < sorted.length; i++) {
+            let delta = sorted[i].value - sorted[i - 1].value;
+            if (delta < 0 && sorted[i].value < -delta) {
+                resets++;
+                delta = sorted[i].value;
+            } else if (delta < 0) {
+                reversals++;
+                delta = 0;
+            }
+            const at = stamp === 'previous' ? sorted[i - 1] : sorted[i];
+            intervals.push({ dt: at.dt, kwh: delta, ...(at.zoned ? { zoned: true } : {}) });
+        }
+        return { intervals, resets, reversals };
+    }
+
+    // Home Assistant energy sensors are total_increasing counters; one sensor is billed
+    function convertHomeAssistant(rows, headerIndex, timeColumn, valueColumn, stamp) {
+        const header = rows[headerIndex].map(c => String(c ?? '').trim().toLowerCase());
+        const timeIdx = header.indexOf(timeColumn);
+        const valueIdx = header.indexOf(valueColumn);
+        const idIdx = header.findIndex(c => c === 'entity_id' || c === 'statistic_id');
+
+        const bySensor = new Map();
+        for (let i = headerIndex + 1; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row) continue;
+            const stamp = parseIsoStamp(row[timeIdx]);
+            const value = parseFloat(row[valueIdx]); // "unavailable" / "unknown" drop out here
+            if (!stamp || isNaN(value)) continue;
+            const id = idIdx === -1 ? '' : String(row[idIdx] ?? '');
+            if (!bySensor.has(id)) bySensor.set(id, []);
+            bySensor.get(id).push({ ...stamp, value });
+        }
+
+        const ids = [...bySensor.keys()];
+        const sensor = ids.find(id => /energy|consumption|kwh/i.test(id)) ?? ids[0];
+        if (sensor === undefined) return { intervals: [], notes: [] };
+        const { intervals, resets, reversals } = registerDeltas(bySensor.get(sensor), stamp);
+
+        const notes = [];
+        if (sensor) notes.push(`sensor ${sensor}`);
+        if (ids.length > 1) notes.push(`ignored ${ids.length - 1} other sensor(s)`);
+        if (resets > 0) notes.push(`${resets} counter reset(s)`);
+        if (reversals > 0) notes.push(`${reversals} reading(s) ran backwards and were billed as zero`);
+        return { intervals, notes };
+    }
+
+    // Sense reports one row per device per hour. The bill follows the "from grid"
+    // channel, not total consumption, whenever solar production is present.
+    function convertSense(rows, headerIndex) {
+        const header = rows[headerIndex].map(c => String(c ?? '').trim().toLowerCase());
+        const timeIdx = header.indexOf('datetime');
+        const idIdx = header.indexOf('device id');
+        const nameIdx = header.indexOf('name');
+        const kwhIdx = header.indexOf('kwh');
+        const channelOf = (id, name) => {
+            if (id === 'from_grid' || /from grid/.test(name)) return 'fromGrid';
+            if (id === 'mains' || /total usage/.test(name)) return 'usage';
+            if (id === 'solar' || /solar/.test(name)) return 'solar';
+            if (id === 'utility' || /utility/.test(name)) return 'utility';
+            return null; // individual devices, always on, other
+        };
+
+        const channels = { fromGrid: new Map(), usage: new Map(), solar: new Map(), utility: new Map() };
+        const zoned = new Set(); // times written with a Z or offset
+        for (let i = headerIndex + 1; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row) continue;
+            const channel = channelOf(String(row[idIdx] ?? '').trim().toLowerCase(), String(row[nameIdx] ?? '').toLowerCase());
+            const stamp = parseIsoStamp(row[timeIdx]);
+            const kwh = parseFloat(row[kwhIdx]);
+            if (!channel || !stamp || isNaN(kwh)) continue;
+            const map = channels[channel];
+            const t = stamp.dt.getTime();
+            map.set(t, (map.get(t) || 0) + kwh);
+            if (stamp.zoned) zoned.add(t);
+        }
+
+        const notes = [];
+        let billed;
+        if (channels.fromGrid.size > 0) {
+            billed = channels.fromGrid;
+            notes.push('from-grid channel');
+        } else if (channels.solar.size > 0) {
+            billed = new Map();
+            channels.usage.forEach((kwh, t) => billed.set(t, Math.max(0, kwh - Math.abs(channels.solar.get(t) || 0))));
+            notes.push('from grid = total usage minus solar production');
+        } else {
+            billed = channels.usage;
+            notes.push('total usage channel');
+        }
+
+        // Check the monitor against the utility meter over the hours both cover
+        if (channels.utility.size > 0) {
+            let monitor = 0, utility = 0;
+            channels.utility.forEach((kwh, t) => {
+                if (!billed.has(t)) return;
+                monitor += billed.get(t);
+                utility += kwh;
+            });
+            if (utility > 0) {
+                const diff = (monitor - utility) / utility * 100;
+                notes.push(`monitor reads ${Math.abs(diff).toFixed(1)}% ${diff < 0 ? 'below' : 'above'} the utility meter`);
+            }
+        }
+
+        const intervals = [...billed.entries()].map(([t, kwh]) => ({ dt: new Date(t), kwh, ...(zoned.has(t) ? { zoned: true } : {}) }));
+        return { intervals, notes };
+    }
+
+    // Emporia exports one file per Vue device with a column per channel. The Mains
+    // channels (one per leg) are the billing load; the others are branch circuits
+    // kept for the circuit breakdown. Timestamps are local "MM/DD/YYYY HH:MM:SS".
+    function convertEmporia(rows, headerIndex) {
+        const header = rows[headerIndex].map(c => String(c ?? '').trim());
+        const timeIdx = header.findIndex(c => c.toLowerCase().startsWith('time bucket'));
+        const channels = header
+            .map((c, idx) => ({ name: c.replace(/\s*\(kwhs\)\s*$/i, ''), idx }))
+            .filter(c => c.idx !== timeIdx && /\(kwhs\)/i.test(header[c.idx]));
+        const mains = channels.filter(c => /^mains/i.test(c.name));
+        const circuits = channels.filter(c => !/^mains/i.test(c.name)).map(c => ({ ...c, kwh: 0 }));
+
+        const intervals = [];
+        for (let i = headerIndex + 1; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row) continue;
+            const match = String(row[timeIdx] ?? '').match(/^(\d{1,2})\/(\d{1,2})\/(\d{4})\s+(\d{1,2}):(\d{2})/);
+            if (!match) continue;
+            const [, mo, d, y, hr, min] = match.map(Number);
+            const dt = new Date(y, mo - 1, d, hr, min);
+            const kwh = mains.reduce((sum, c) => sum + (parseFloat(row[c.idx]) || 0), 0);
+            intervals.push({ dt, kwh });
+            circuits.forEach(c => { c.kwh += parseFloat(row[c.idx]) || 0; });
+        }
+
+        const notes = mains.length > 0 ? [`billed ${mains.map(c => c.name).join(' + ')}`] : ['no Mains channel found'];
+        return { intervals, notes, circuits: circuits.map(({ name, kwh }) => ({ name, kwh })) };
+    }
+
+    // eGauge CSV has a "Date & Time" column (epoch seconds or text) and one "<name> [kWh]"
+    // column per register, newest row first. Values are register readings unless the
+    // export was taken with deltas. The grid register is billed (imports only); without
+    // one, the usage register is.
+    function convertEgauge(rows, headerIndex) {
+        const header = rows[headerIndex].map(c => String(c ?? '').trim());
+        const timeIdx = header.findIndex(c => c.toLowerCase() === 'date & time');
+        const registers = header
+            .map((c, idx) => ({ name: c.replace(/\s*\[kwh\]\s*$/i, ''), idx }))
+            .filter(c => /\[kwh\]$/i.test(header[c.idx]));
+        const billedRegister = registers.find(r => /^grid\b/i.test(r.name))
+            ?? registers.find(r => /^(usage|use)\b/i.test(r.name))
+            ?? registers[0];
+        if (!billedRegister) return { intervals: [], notes: [] };
+
+        const readings = [];
+        for (let i = headerIndex + 1; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row) continue;
+            const stamp = parseIsoStamp(row[timeIdx]);
+            const value = parseFloat(row[billedRegister.idx]);
+            if (stamp && !isNaN(value)) readings.push({ ...stamp, value });
+        }
+        readings.sort((a, b) => a.dt - b.dt);
+
+        const notes = [`${billedRegister.name} register`];
+        const rising = readings.filter((r, i) => i > 0 && r.value >= readings[i - 1].value).length;
+        if (readings.length < 2 || rising / (readings.length - 1) < 0.95) {
+            // Already per-interval values, each stamped at the end of its interval
+            const intervals = readings.slice(1).map((r, i) => ({ dt: readings[i].dt, kwh: Math.max(0, r.value), ...(readings[i].zoned ? { zoned: true } : {}) }));
+            return { intervals, notes: [...notes, 'interval values'] };
+        }
+
+        const { intervals, resets, reversals } = registerDeltas(readings, 'previous');
+        if (resets > 0) notes.push(`${resets} counter reset(s)`);
+        if (reversals > 0) notes.push(`${reversals} interval(s) of net export billed as zero`);
+        return { intervals, notes };
+    }
+
+    // Green Button (ESPI) XML from a utility portal: an Atom feed whose IntervalBlock entries
+    // hold IntervalReading { timePeriod { start (Unix seconds), duration (s) }, value }.
+    // Values are in the units of the ReadingType their MeterReading links to (uom 72 = Wh,
+    // scaled by 10^powerOfTenMultiplier); flowDirection 19 marks energy sent back to the grid.
+    // Converted to rows of [start, duration, kWh, direction] for the adapter below.
+    function greenButtonXmlToRows(text) {
+        const doc = new DOMParser().parseFromString(text, 'application/xml');
+        if (doc.getElementsByTagName('parsererror').length > 0) {
+            throw new Error('The XML file could not be read.');
+        }
+        const byLocalName = (node, name) => Array.from(node.getElementsByTagNameNS('*', name));
+        const childText = (node, name) => {
+            const found = byLocalName(node, name)[0];
+            return found ? found.textContent.trim() : null;
+        };
+        const links = entry => byLocalName(entry, 'link').map(l => ({ rel: l.getAttribute('rel'), href: l.getAttribute('href') || '' }));
+        const selfHref = entry => (links(entry).find(l => l.rel === 'self') || { href: '' }).href;
+
+        const entries = byLocalName(doc, 'entry');
+        const readingTypes = new Map();
+        entries.forEach(entry => {
+            const type = byLocalName(entry, 'ReadingType')[0];
+            if (!type) return;
+            readingTypes.set(selfHref(entry), {
+                scale: Math.pow(10, parseInt(childText(type, 'powerOfTenMultiplier') || '0', 10)),
+                uom: childText(type, 'uom'),
+                direction: childText(type, 'flowDirection') === '19' ? 'received' : 'delivered'
+            });
+        });
+        // MeterReading self href -> the ReadingType it points to
+        const meterTypes = new Map();
+        entries.filter(entry => byLocalName(entry, 'MeterReading').length > 0).forEach(entry => {
+            const related = links(entry).filter(l => l.rel === 'related').map(l => readingTypes.get(l.href)).find(Boolean);
+            if (related) meterTypes.set(selfHref(entry), related);
+        });
+        const fallbackType = readingTypes.values().next().value || { scale: 1, uom: '72', direction: 'delivered' };
+
+        const rows = [['Green Button start', 'Duration', 'kWh', 'Direction']];
+        entries.filter(entry => byLocalName(entry, 'IntervalBlock').length > 0).forEach(entry => {
+            // An IntervalBlock's "up" link is its MeterReading's href plus "/IntervalBlock"
+            const up = links(entry).find(l => l.rel === 'up');
+            const type = (up && meterTypes.get(up.href.replace(/\/IntervalBlock\/?$/, ''))) || fallbackType;
+            const toKwh = type.uom === '72' || type.uom === null ? type.scale / 1000 : type.scale;
+            byLocalName(entry, 'IntervalReading').forEach(reading => {
+                const start = parseInt(childText(reading, 'start'), 10);
+                const value = parseFloat(childText(reading, 'value'));
+                if (isNaN(start) || isNaN(value)) return;
+                rows.push([start, parseInt(childText(reading, 'duration'), 10) || 3600, value * toKwh, type.direction]);
+            });
+        });
+        return rows;
+    }
 
-        reader.onload = (e) => {
+    // SAM's hourly results table, exported as CSV. The bill follows the power bought from the
+    // grid: the "... from grid" columns (to the load and to a battery) added together, or the
+    // purchases in the signed "Electricity to/from grid" column when those aren't included.
+    function convertSam(rows, headerIndex) {
+        const header = rows[headerIndex].map(c => String(c ?? '').trim().toLowerCase());
+        const fromGrid = header.map((c, i) => (/from grid \| \(kw\)$/.test(c) && !/to\/from grid/.test(c) ? i : -1)).filter(i => i !== -1);
+        const signed = header.findIndex(c => /to\/from grid \| \(kw\)$/.test(c));
+        const loadIdx = header.findIndex(c => /^electricity load.*\| \(kw\)$/.test(c));
+        const data = rows.slice(headerIndex + 1).filter(r => r && r.length > 0);
+        const grid = data.map(r => (fromGrid.length > 0
+            ? fromGrid.reduce((sum, i) => sum + (Number(r[i]) || 0), 0)
+            : Math.max(0, -(Number(r[signed]) || 0)))); // SAM counts power sent to the grid as positive
+        return convertDispatch(grid, loadIdx === -1 ? null : data.map(r => Number(r[loadIdx]) || 0), null);
+    }
+
+    // Grid purchases from a SAM or REopt run, each series kW per time step. When the results
+    // include the load before solar and storage, the note compares the bills with and without
+    // them, the savings claim to check.
+    function convertDispatch(grid, load, year) {
+        const dispatch = dispatchSeries(grid, year);
+        if (!dispatch) {
+            return { intervals: [], notes: [`${grid.length} time steps don't make up a year of whole steps per hour (8,760 for hourly results)`] };
+        }
+        const notes = [`${dispatch.stepMinutes}-minute steps on a ${dispatch.year} calendar, moved from standard time to the Georgia clock`];
+        const without = load && dispatchSeries(load, year);
+        if (without) {
+            const sumKwh = series => series.reduce((n, r) => n + r.kwh, 0);
+            const onGeorgiaClock = series => toGeorgiaTime(series, GEORGIA_TIME_ZONE).series;
+            const before = calculateBills(onGeorgiaClock(without.series)).totals;
+            const after = calculateBills(onGeorgiaClock(dispatch.series)).totals;
+            notes.push(`grid purchases ${Math.round(sumKwh(dispatch.series)).toLocaleString()} kWh of a ${Math.round(sumKwh(without.series)).toLocaleString()} kWh load`);
+            notes.push(`bills without -> with the system: ${PLAN_IDS.map(id =>
+                `${RATE_PLANS[id].name} $${before[id].total.toFixed(2)} -> $${after[id].total.toFixed(2)} (saves $${(before[id].total - after[id].total).toFixed(2)})`).join(', ')}`);
+        }
+        return { intervals: dispatch.series, notes };
+    }
+
+    // JSON records (see RateBilling.parseUsageJson) laid out as rows for the 'json' adapter, or
+    // REopt results (see RateBilling.readReoptResults) as rows for the 'reopt' adapter
+    function jsonToRows(text) {
+        if (text.trim().startsWith('{')) {
+            let reopt = null;
             try {
-                const data = new Uint8Array(e.target.result);
-                const workbook = XLSX.read(data, { type: 'array' });
-                const firstSheetName = workbook.SheetNames[0];
-                const worksheet = workbook.Sheets[firstSheetName];
-                const json = XLSX.utils.sheet_to_json(worksheet, { header: 1 }); // Array of arrays
-                processData(json);
+                reopt = readReoptResults(JSON.parse(text));
             } catch (err) {
-                showError('Error processing Excel file: ' + err.message);
-                console.error(err);
+                // Not a single JSON object; JSON Lines records are read below
+            }
+            if (reopt) return [['REopt timestep', 'Grid kW', 'Load kW', 'Year'], ...reopt.grid.map((kw, i) => [i, kw, reopt.load ? reopt.load[i] : '', reopt.year || ''])];
+        }
+        let series;
+        try {
+            series = parseUsageJson(text);
+        } catch (err) {
+            throw new Error(`The JSON could not be read (${err.message}).`);
+        }
+        if (series.length === 0) throw new Error('No {"timestamp": ..., "kwh": ...} records were found in the JSON.');
+        return [['JSON timestamp', 'kWh', 'Zoned'], ...series.map(r => [r.dt.getTime(), r.kwh, r.zoned ? 1 : 0])];
+    }
+
+    // Energy delivered from the grid is what the bill charges for. Readings longer than an hour
+    // (daily Green Button data) are spread evenly over their hours, which TOU periods can't check.
+    function convertGreenButton(rows, headerIndex) {
+        const intervals = [];
+        let received = 0;
+        let spread = 0;
+        rows.slice(headerIndex + 1).forEach(([start, duration, kwh, direction]) => {
+            if (direction === 'received') {
+                received++;
+                return;
             }
+            const hours = Math.max(1, Math.round(duration / 3600));
+            if (hours > 1) spread++;
+            for (let h = 0; h < hours; h++) {
+                intervals.push({ dt: new Date((start + h * 3600) * 1000), kwh: Math.max(0, kwh) / hours, zoned: true });
+            }
+        });
+        const notes = [];
+        if (received > 0) notes.push(`${received} reading(s) of energy sent to the grid left out`);
+        if (spread > 0) notes.push(`${spread} reading(s) longer than an hour spread evenly over their hours`);
+        return { intervals, notes };
+    }
+
+    // eGauge XML (egauge-show) holds <data time_stamp time_delta> blocks of <r><c>
+    // rows counting back from time_stamp, with cumulative watt-seconds per <cname>.
+    // Converted to the CSV layout above so one adapter handles both.
+    function egaugeXmlToRows(text) {
+        const doc = new DOMParser().parseFromString(text, 'application/xml');
+        if (doc.getElementsByTagName('parsererror').length > 0) {
+            throw new Error('The XML file could not be read.');
+        }
+        let names = [];
+        const rows = [];
+        Array.from(doc.getElementsByTagName('data')).forEach(data => {
+            const cnames = Array.from(data.getElementsByTagName('cname'));
+            if (cnames.length > 0) names = cnames.map(c => c.textContent.trim());
+            let stamp = parseInt(data.getAttribute('time_stamp'), 16);
+            const delta = parseInt(data.getAttribute('time_delta'), 10) || 60;
+            Array.from(data.getElementsByTagName('r')).forEach(r => {
+                const values = Array.from(r.getElementsByTagName('c')).map(c => parseFloat(c.textContent) / 3.6e6);
+                rows.push([String(stamp), ...values]);
+                stamp -= delta;
+            });
+        });
+        return [['Date & Time', ...names.map(n => `${n} [kWh]`)], ...rows];
+    }
+
+    function runAnalysis() {
+        if (!lastRows) return;
+        try {
+            processData(lastRows);
+        } catch (err) {
+            showError('Error analyzing data: ' + err.message);
+            console.error(err);
+        }
+    }
+
+    // Options can be preset from the page URL, e.g. index.html?start=2024-04-01&end=2025-01-31&plans=tou-oa,r30&sqft=1800
+    function applyQueryParams() {
+        const params = new URLSearchParams(window.location.search);
+        const dateParam = name => {
+            const value = params.get(name);
+            return value && /^\d{4}-\d{2}-\d{2}$/.test(value) ? value : null;
         };
-        reader.readAsArrayBuffer(file);
+        if (dateParam('start')) document.getElementById('range-start').value = dateParam('start');
+        if (dateParam('end')) document.getElementById('range-end').value = dateParam('end');
+        // Home size, so a bookmark per property keeps its own normalization
+        ['sqft', 'occupants'].forEach(name => {
+            const value = parseFloat(params.get(name));
+            if (value > 0) document.getElementById(`home-${name}`).value = value;
+        });
+        if (params.has('plans')) {
+            const wanted = params.get('plans').split(',').map(findPlanId);
+            PLAN_IDS.forEach(id => {
+                document.getElementById(`compare-${id}`).checked = wanted.includes(id);
+            });
+        }
+    }
+
+    // Plans ticked under "Plans to compare"; all of them when none are ticked
+    function getSelectedPlans() {
+        const selected = PLAN_IDS.filter(id => document.getElementById(`compare-${id}`).checked);
+        return selected.length > 0 ? selected : PLAN_IDS;
     }
 
-    function showError(msg) {
+    // Selected date range from the options panel; end date is inclusive
+    function getRequestedRange() {
+        const toDate = (value, addDays = 0) => {
+            if (!value) return null;
+            const [y, m, d] = value.split('-').map(Number);
+            return new Date(y, m - 1, d + addDays);
+        };
+        return {
+            start: toDate(document.getElementById('range-start').value),
+            end: toDate(document.getElementById('range-end').value, 1)
+        };
+    }
+
+    function showError(msg, details = []) {
         errorMessage.textContent = msg;
+        if (details.length > 0) {
+            const list = document.createElement('ul');
+            list.className = 'error-details';
+            details.forEach(d => {
+                const item = document.createElement('li');
+                item.textContent = d;
+                list.appendChild(item);
+            });
+            errorMessage.appendChild(list);
+        }
         errorMessage.classList.remove('hidden');
         resultsSection.classList.add('hidden');
+        validationSection.classList.add('hidden');
+    }
+
+    // Header names typed under Timestamp column / kWh column, blank for auto-detect
+    function usageColumnNames() {
+        return ['timestamp-column', 'kwh-column'].map(id => document.getElementById(id).value.trim());
+    }
+
+    // The header row of a GA Power layout sheet and its columns, or null. Exports put zero or
+    // more disclaimer lines above the header, so every row is checked until one has both an
+    // "Hour" and a "kWh" column.
+    function findUsageHeader(rows, names = usageColumnNames()) {
+        for (let i = 0; i < rows.length; i++) {
+            const row = rows[i];
+            if (!row || row.length === 0) continue;
+
+            // Look for "Hour" and "kWh" (case insensitive); 15- and 30-minute exports may
+            // label the timestamp "Interval" or "Time" instead. Named columns replace the guess.
+            const findColumn = word => row.findIndex(c => c && c.toString().toLowerCase().includes(word));
+            const guessed = [
+                [findColumn('hour'), findColumn('interval'), findColumn('time')].find(idx => idx !== -1) ?? -1,
+                findColumn('kwh') // "kWh" or "Usage Amount (kWh)"
+            ];
+            const [hourIdx, kwhIdx] = findNamedColumns(row, names, guessed) || [-1, -1];
+            if (hourIdx === -1 || kwhIdx === -1) continue;
+
+            return {
+                index: i,
+                colMap: {
+                    timestamp: hourIdx,
+                    kwh: kwhIdx,
+                    // Optional flag column for readings the utility estimated instead of metering
+                    estimated: [findColumn('estimat'), findColumn('read type')].find(idx => idx !== -1) ?? -1,
+                    // Optional meter ID, for files that span a meter replacement
+                    meter: row.findIndex((c, idx) => idx !== hourIdx && idx !== kwhIdx && c && /meter/i.test(c.toString()))
+                }
+            };
+        }
+        return null;
     }
 
     function processData(rows) {
         errorMessage.classList.add('hidden');
 
-        // Find header row
-        let headerRowIndex = -1;
-        let colMap = { timestamp: -1, kwh: -1 };
+        const header = findUsageHeader(rows);
+        if (!header) {
+            const firstRow = rows.find(r => r && r.length > 0);
+            const [timestampName, kwhName] = usageColumnNames();
+            const timestampLabel = timestampName ? `"${timestampName}"` : '"Hour" (or "Interval")';
+            showError(`Could not find a header row with ${timestampLabel} and "${kwhName || 'kWh'}" columns anywhere in the file.`, [
+                `Rows in sheet: ${rows.length}`,
+                firstRow ? `First non-empty row: ${firstRow.join(' | ')}` : 'The sheet is empty.',
+                'Suggestion: export the Hourly view in Table mode; the Daily and Monthly exports use different columns. For another layout, type its header names under Timestamp column and kWh column.'
+            ]);
+            return;
+        }
+        const { index: headerRowIndex, colMap } = header;
+
+        let records = [];
+        // Zero-kWh rows aren't billed but were read, so the gap check counts them as present
+        let zeroReadings = [];
+        const sampleLimit = parseInt(document.getElementById('show-skipped').value, 10) || 0;
+        const timestampFormat = document.getElementById('timestamp-format').value;
+        const timeZone = document.getElementById('time-zone').value || GEORGIA_TIME_ZONE;
+        const dedupePolicy = document.getElementById('dedupe-policy').value || 'first';
+        const rowStats = { headerRow: headerRowIndex + 1, scanned: 0, skipped: {}, samples: {}, firstSeen: null, lastSeen: null, formats: {}, conflicts: [], repeated: null, midnight24: 0, repeatedHour: [], dedupePolicy };
+        const skip = (reason, rowIndex) => {
+            rowStats.skipped[reason] = (rowStats.skipped[reason] || 0) + 1;
+            const samples = rowStats.samples[reason] || (rowStats.samples[reason] = []);
+            if (samples.length < sampleLimit) {
+                samples.push({ line: rowIndex + 1, text: (rows[rowIndex] || []).join(' | ') });
+            }
+        };
+        const seenTimestamps = new Map(); // time -> { kWh first read, index in records }
 
-        for (let i = 0; i < Math.min(rows.length, 20); i++) {
+        for (let i = headerRowIndex + 1; i < rows.length; i++) {
             const row = rows[i];
-            if (!row || row.length === 0) continue;
+            if (!row || row.length <= Math.max(colMap.timestamp, colMap.kwh)) {
+                skip('blank', i);
+                continue;
+            }
+            rowStats.scanned++;
+
+            const timestampStr = row[colMap.timestamp];
+            const kwhVal = row[colMap.kwh];
+
+            if (timestampStr === undefined || timestampStr === null) {
+                skip('badTimestamp', i);
+                continue;
+            }
+
+            let dt = null;
+            let format;
+            let zoned = false;
+            if (typeof timestampStr === 'number') {
+                // Excel serial date, rounded to the minute: the fraction of a day rarely comes
+                // out exact, and without rounding 14:15 can read back as 14:14:59.999
+                const dateObj = new Date(Math.round((timestampStr - 25569) * 1440) * 60 * 1000);
+                dt = new Date(dateObj.getUTCFullYear(), dateObj.getUTCMonth(), dateObj.getUTCDate(), dateObj.getUTCHours(), dateObj.getUTCMinutes());
+                format = 'Excel serial date';
+            } else {
+                const parsed = parseTimestampText(timestampStr.toString(), timestampFormat);
+                if (parsed) ({ dt, format, zoned = false } = parsed);
+                if (parsed && parsed.midnight24) rowStats.midnight24++;
+            }
+
+            if (!dt || isNaN(dt.getTime())) {
+                skip('badTimestamp', i);
+                continue;
+            }
+            rowStats.formats[format] = (rowStats.formats[format] || 0) + 1;
+            if (!rowStats.firstSeen || dt < rowStats.firstSeen) rowStats.firstSeen = dt;
+            if (!rowStats.lastSeen || dt > rowStats.lastSeen) rowStats.lastSeen = dt;
+
+            let kwh = parseFloat(kwhVal);
+            if (isNaN(kwh)) {
+                skip('badKwh', i);
+                continue;
+            }
+            if (kwh < 0) {
+                skip('negative', i);
+                continue;
+            }
+
+            // Filter out zero usage
+            if (kwh <= 0.001) {
+                skip('zero', i);
+                zeroReadings.push({ dt, kwh: 0, ...(zoned ? { zoned } : {}) });
+                continue;
+            }
+
+            // Overlapping exports repeat hours; the duplicate policy settles which reading counts,
+            // and copies that disagree are remembered since then neither can be trusted. Two
+            // meters reading the same hour during a replacement are sorted out by chooseMeters
+            // below. A second reading of the hour the clocks repeat in November gets its own seen
+            // key and is marked repeated, because it is that hour's standard-time pass rather
+            // than a copy of the daylight-time one.
+            const meter = colMap.meter === -1 ? '' : String(row[colMap.meter] ?? '').trim();
+            let seenKey = colMap.meter === -1 ? dt.getTime() : `${meter} ${dt.getTime()}`;
+            let again = false;
+            if (seenTimestamps.has(seenKey) && !zoned && !seenTimestamps.has(`${seenKey} again`) && clockChange(dt, timeZone) === 'repeated') {
+                seenKey = `${seenKey} again`;
+                again = true;
+                if (!rowStats.repeatedHour.some(d => d.getTime() === dt.getTime())) rowStats.repeatedHour.push(dt);
+            }
+            const estimated = colMap.estimated !== -1 && /^(y|yes|true|1|e|est)$|estimat/i.test(String(row[colMap.estimated] ?? '').trim());
+            const record = { dt, kwh, rowIndex: i, ...(estimated ? { estimated } : {}), ...(meter ? { meter } : {}), ...(zoned ? { zoned } : {}), ...(again ? { repeated: true } : {}) };
+            const first = seenTimestamps.get(seenKey);
+            if (first) {
+                if (Math.abs(first.kwh - kwh) > 0.001) rowStats.conflicts.push(dt);
+                const repeated = rowStats.repeated || (rowStats.repeated = { from: dt, to: dt });
+                if (dt < repeated.from) repeated.from = dt;
+                if (dt > repeated.to) repeated.to = dt;
+                try {
+                    records[first.index] = DEDUPE_POLICIES[dedupePolicy].resolve(records[first.index], record);
+                } catch (err) {
+                    showError(err.message, [`Line ${i + 1}: ${row.join(' | ')}`, 'Pick another duplicate policy under Analysis Options to settle repeated readings.']);
+                    return;
+                }
+                skip('duplicate', i);
+                continue;
+            }
+            seenTimestamps.set(seenKey, { kwh, index: records.length });
+            records.push(record);
+        }
+
+        if (records.length === 0) {
+            showError('No usable usage rows were found in this file.', describeRowStats(rowStats));
+            return;
+        }
+
+        // Sort by date ascending
+        records.sort((a, b) => a.dt - b.dt);
+
+        const cutovers = parseCutovers(document.getElementById('meter-cutover').value);
+        if (cutovers.errors.length > 0) {
+            showError('Could not read the meter change date(s).', cutovers.errors);
+            return;
+        }
+        const meters = chooseMeters(records, cutovers.dates);
+        meters.otherMeter.forEach(r => skip('otherMeter', r.rowIndex));
+        records = meters.records;
+        rowStats.meterChanges = meters.notes;
+
+        // A few rows dated 1970 or 2099 would otherwise move the full-year cutoff to them
+        const strays = setAsideStrayDates(records, document.getElementById('repair-dates').checked);
+        strays.setAside.forEach(r => skip('strayDate', r.rowIndex));
+        records = strays.records;
+        rowStats.strayDates = strays.notes;
+
+        // Clock times to Georgia time, which the TOU periods are written in
+        const moved = toGeorgiaTime(records, timeZone);
+        records = moved.series;
+        zeroReadings = toGeorgiaTime(zeroReadings.sort((a, b) => a.dt - b.dt), timeZone).series;
+        rowStats.timeZone = { zone: timeZone, skipped: moved.skipped, zoned: records.filter(r => r.zoned).length };
+
+        // Readings labeled by the end of their interval are moved back to its start
+        const labelChoice = document.getElementById('interval-label').value;
+        const detectedLabel = detectIntervalLabel(records, { header: String(rows[headerRowIndex][colMap.timestamp] ?? '').trim(), midnight24: rowStats.midnight24 });
+        rowStats.intervalLabel = labelChoice ? { label: labelChoice, reason: 'chosen under Analysis Options' } : detectedLabel;
+        if (rowStats.intervalLabel.label === 'end') {
+            rowStats.intervalLabel.minutes = detectIntervalMinutes(records) || 60;
+            records = toIntervalStart(records, rowStats.intervalLabel.minutes);
+            zeroReadings = toIntervalStart(zeroReadings, rowStats.intervalLabel.minutes);
+        }
+
+        // Constant clock correction, applied before anything is classified
+        const offsetMinutes = parseFloat(document.getElementById('time-offset').value) || 0;
+        if (offsetMinutes !== 0) {
+            [...records, ...zeroReadings].forEach(r => { r.dt = new Date(r.dt.getTime() + offsetMinutes * 60 * 1000); });
+        }
+
+        // Pipeline file steps, after the built-in cleanup and before any range selection
+        rowStats.pipeline = null;
+        if (loadedPipeline) {
+            const piped = applyPipeline(records, loadedPipeline.config);
+            records = piped.series;
+            rowStats.pipeline = { name: loadedPipeline.name, log: piped.log };
+            if (records.length === 0) {
+                showError(`The steps in ${loadedPipeline.name} left no usage rows.`, describePipeline(rowStats.pipeline));
+                return;
+            }
+        }
+
+        if (document.getElementById('dry-run').checked) {
+            const present = [...records, ...zeroReadings].sort((a, b) => a.dt - b.dt);
+            const minutes = detectIntervalMinutes(records) || 60;
+            const gapFill = document.getElementById('gap-fill').value;
+            rowStats.gaps = { ...findGaps(present, minutes), minutes };
+            rowStats.gapFill = gapFill ? { method: gapFill, readings: fillGaps(present, gapFill, { minutes }).added.length } : null;
+            renderValidation(records, rowStats);
+            return;
+        }
+        validationSection.classList.add('hidden');
+
+        // --- Date Range Logic ---
+        const dataStart = records[0].dt;
+        const dataEnd = records[records.length - 1].dt;
+        // The last reading covers one more interval, e.g. 23:00 runs to midnight
+        const coverageEnd = new Date(dataEnd.getTime() + (detectIntervalMinutes(records) || 60) * 60 * 1000);
+        const requested = getRequestedRange();
+
+        let usedRecords = records;
+        let note = "";
+
+        if (requested.start || requested.end) {
+            // 1. An explicit range replaces the automatic full-year selection
+            usedRecords = records.filter(r => (!requested.start || r.dt >= requested.start) && (!requested.end || r.dt < requested.end));
+            countOutOfRange(records, usedRecords, skip);
+            if (usedRecords.length === 0) {
+                showError('No data falls inside the selected date range.', [
+                    `Selected: ${formatRange(requested.start, requested.end)}`,
+                    `Data available: ${dataStart.toLocaleDateString()} - ${dataEnd.toLocaleDateString()}`,
+                    `Suggestion: pick dates between ${dataStart.toLocaleDateString()} and ${dataEnd.toLocaleDateString()}, or clear the range to use all data.`
+                ]);
+                return;
+            }
+            note = "Using the selected date range.";
+        } else if (fullYearsBetween(dataStart, coverageEnd) >= 1) {
+            // 2. Truncate to most recent full calendar years if > 1 year (366 days when Feb 29 falls inside)
+            const fullYears = fullYearsBetween(dataStart, coverageEnd);
+            const cutoffDate = addYears(coverageEnd, -fullYears);
+            usedRecords = records.filter(r => r.dt >= cutoffDate);
+            countOutOfRange(records, usedRecords, skip);
+            note = `Using most recent ${fullYears} full year(s) of data for accurate seasonal comparison.`;
+        } else {
+            note = "Less than 1 year of data. Seasonal variations may affect accuracy.";
+        }
+
+        // Re-calculate stats for used records
+        const effectiveStart = usedRecords[0].dt;
+        const effectiveEnd = usedRecords[usedRecords.length - 1].dt;
+        const effectiveDuration = daysBetween(effectiveStart, effectiveEnd);
+
+        if (effectiveDuration < 30) {
+            showError(`Insufficient data: ${effectiveDuration.toFixed(1)} days found. At least 30 days are required for an accurate recommendation.`,
+                describeRowStats(rowStats, usedRecords.length));
+            return;
+        }
+
+        const coverage = {
+            requestedStart: requested.start,
+            requestedEnd: requested.end,
+            dataStart,
+            dataEnd,
+            missingMonths: findMissingMonths(usedRecords),
+            strayDates: strays.notes,
+            meterChanges: rowStats.meterChanges,
+            fileOverlap: describeFileOverlap(rowStats)
+        };
+
+        // Missing readings make a month's bill look low, so they are reported per month
+        const intervalMins = detectIntervalMinutes(usedRecords) || 60;
+        const zerosUsed = zeroReadings.filter(r => r.dt >= effectiveStart && r.dt <= effectiveEnd);
+        coverage.gaps = { ...findGaps([...usedRecords, ...zerosUsed].sort((a, b) => a.dt - b.dt), intervalMins), minutes: intervalMins };
+        // With a fill method chosen, the gaps are filled once counted, so Data Coverage still lists them
+        const gapFill = document.getElementById('gap-fill').value;
+        if (gapFill && coverage.gaps.gaps.length > 0) {
+            const { added } = fillGaps([...usedRecords, ...zerosUsed].sort((a, b) => a.dt - b.dt), gapFill, { minutes: intervalMins });
+            usedRecords = [...usedRecords, ...added].sort((a, b) => a.dt - b.dt);
+            coverage.gaps.filled = { method: gapFill, readings: added.length };
+            note += ` ${describeMissing(coverage.gaps)}, filled ${GAP_FILL_METHODS[gapFill]} and counted as estimated (see Data Coverage).`;
+        } else if (coverage.gaps.gaps.length > 0) {
+            note += ` ${describeMissing(coverage.gaps)}, so those months' bills run low (see Data Coverage).`;
+        }
+        const demandWindow = SERVICE_RATES['tou-rd'].demandWindowMinutes;
+        if (intervalMins < demandWindow) {
+            note += ` Readings are ${intervalMins} minutes apart; TOU-RD demand is taken from their ${demandWindow}-minute totals.`;
+        }
+
+        const addons = getActiveAddons();
+        if (addons.length > 0) {
+            usedRecords = applyLoadAddons(usedRecords, addons);
+            note += ` Includes what-if add-ons: ${addons.map(a => a.label).join(', ')}.`;
+        }
+
+        if (rowStats.intervalLabel.label === 'end') {
+            note += ` ${describeIntervalLabel(rowStats.intervalLabel)}.`;
+        }
+        if (rowStats.timeZone.zone !== GEORGIA_TIME_ZONE || rowStats.repeatedHour.length > 0) {
+            note += ` ${describeTimeZone(rowStats)}.`;
+        }
+        const duplicates = describeDuplicates(rowStats);
+        if (duplicates) {
+            note += ` ${duplicates}.`;
+        }
+        if (rowStats.pipeline) {
+            note += ` Preprocessed by ${rowStats.pipeline.name}: ${rowStats.pipeline.log.map(l => `${l.step} (${l.detail})`).join(', ')}.`;
+        }
+
+        const clock = { offsetMinutes, check: detectClockSkew(usedRecords) };
+
+        calculateCosts(usedRecords, { duration: effectiveDuration, note, coverage, rowStats, clock });
+    }
+
+    function parseDate(str) {
+        // "2025-02-19 23:00"
+        const [datePart, timePart] = str.split(' ');
+        if (!datePart || !timePart) return null;
+        const [y, m, d] = datePart.split('-').map(Number);
+        const [hr, min] = timePart.split(':').map(Number);
+        return new Date(y, m - 1, d, hr, min);
+    }
+
+    // Text timestamp layouts, tried in this order when the format is left on auto-detect.
+    // Seconds and an AM/PM suffix are optional everywhere; DD/MM/YYYY is never guessed because
+    // it reads the same as MM/DD/YYYY for the first twelve days of every month.
+    const TIMESTAMP_FORMATS = [
+        { id: 'ymd', label: 'YYYY-MM-DD HH:MM text', auto: true, pattern: /^(\d{4})-(\d{1,2})-(\d{1,2})[ T](\d{1,2}):(\d{2})(?::(\d{2})(?:\.\d+)?)?\s*([AP]M)?$/i, order: ['y', 'm', 'd'] },
+        { id: 'iso', label: 'ISO 8601 with time zone', auto: true, pattern: /^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})$/i },
+        { id: 'mdy', label: 'MM/DD/YYYY HH:MM text', auto: true, pattern: /^(\d{1,2})\/(\d{1,2})\/(\d{4}) (\d{1,2}):(\d{2})(?::(\d{2}))?\s*([AP]M)?$/i, order: ['m', 'd', 'y'] },
+        { id: 'dmy', label: 'DD/MM/YYYY HH:MM text', auto: false, pattern: /^(\d{1,2})\/(\d{1,2})\/(\d{4}) (\d{1,2}):(\d{2})(?::(\d{2}))?\s*([AP]M)?$/i, order: ['d', 'm', 'y'] }
+    ];
+
+    // Parse a text timestamp with the chosen format id, or the auto-detect list when formatId is
+    // empty. Returns { dt, format } naming the layout that matched, or null; zoned is set when
+    // the text carried a Z or offset and so names an exact instant.
+    function parseTimestampText(str, formatId) {
+        const text = str.trim();
+        const formats = formatId ? TIMESTAMP_FORMATS.filter(f => f.id === formatId) : TIMESTAMP_FORMATS.filter(f => f.auto);
+        for (const f of formats) {
+            const m = f.pattern.exec(text);
+            if (!m) continue;
+            if (!f.order) return { dt: new Date(text), format: f.label, zoned: true };
+            const part = {};
+            f.order.forEach((key, i) => { part[key] = Number(m[i + 1]); });
+            let hr = Number(m[4]);
+            const ampm = m[7] && m[7].toUpperCase();
+            if (ampm && (hr < 1 || hr > 12)) return null;
+            if (ampm === 'AM' && hr === 12) hr = 0;
+            if (ampm === 'PM' && hr < 12) hr += 12;
+            // 24:00 is the midnight that ends the day, written by exports that label intervals by their end
+            const midnight24 = !ampm && hr === 24 && Number(m[5]) === 0 && !Number(m[6] || 0);
+            const day = new Date(part.y, part.m - 1, part.d);
+            // Reject roll-overs such as 02/30 rather than quietly moving them into March
+            if (day.getMonth() !== part.m - 1 || day.getDate() !== part.d || (hr > 23 && !midnight24)) return null;
+            const dt = new Date(part.y, part.m - 1, part.d, hr, Number(m[5]), Number(m[6] || 0));
+            return { dt, format: ampm ? f.label.replace('HH:MM', 'h:mm AM/PM') : f.label, ...(midnight24 ? { midnight24 } : {}) };
+        }
+        return null;
+    }
+
+    const SKIP_REASONS = {
+        blank: 'blank or short row',
+        badTimestamp: 'unreadable timestamp',
+        badKwh: 'non-numeric kWh',
+        negative: 'negative kWh',
+        zero: 'zero usage',
+        duplicate: 'duplicate timestamp',
+        strayDate: 'implausible date set aside',
+        otherMeter: 'meter not in service at the time',
+        outOfRange: 'outside analyzed range'
+    };
+
+    // Time zone choices under Analysis Options, by IANA name
+    const TIME_ZONE_NAMES = {
+        'UTC': 'UTC',
+        'Etc/GMT+5': 'Eastern Standard Time all year',
+        'America/Chicago': 'US Central time',
+        'America/Denver': 'US Mountain time',
+        'America/Los_Angeles': 'US Pacific time'
+    };
+
+    // How timestamps were placed on the Georgia clock, for the note and the dry-run report
+    function describeTimeZone(rowStats) {
+        const { zone, skipped, zoned } = rowStats.timeZone;
+        const parts = [zone === GEORGIA_TIME_ZONE
+            ? 'Timestamps read as Georgia time'
+            : `Timestamps read as ${TIME_ZONE_NAMES[zone] || zone} and converted to Georgia time`];
+        if (zoned > 0) parts.push(`${zoned} carried their own UTC offset`);
+        if (rowStats.repeatedHour.length > 0) {
+            parts.push(`the hour clocks repeat on ${rowStats.repeatedHour.map(d => d.toLocaleDateString()).join(', ')} was kept twice rather than dropped as a duplicate`);
+        }
+        if (skipped > 0) {
+            parts.push(zone === GEORGIA_TIME_ZONE
+                ? `${skipped} reading(s) fall in the hour clocks skip in March, so the file may be in UTC or standard time all year (see Time zone under Analysis Options)`
+                : `${skipped} reading(s) fall in an hour those clocks skip and were read as the hour after`);
+        }
+        return parts.join('; ');
+    }
+
+    function describeIntervalLabel(intervalLabel) {
+        return intervalLabel.label === 'end'
+            ? `Timestamps mark the end of each interval (${intervalLabel.reason}), so readings were moved back ${intervalLabel.minutes} min`
+            : `interval start (${intervalLabel.reason})`;
+    }
+
+    // One line per pipeline step, in the order the steps ran
+    function describePipeline(pipeline) {
+        return pipeline.log.map((l, i) => `${i + 1}. ${l.step}: ${l.detail}`);
+    }
+
+    function countOutOfRange(records, usedRecords, skip) {
+        const used = new Set(usedRecords);
+        records.forEach(r => {
+            // Readings a pipeline step filled in have no source row to count
+            if (!used.has(r) && r.rowIndex !== undefined) skip('outOfRange', r.rowIndex);
+        });
+    }
+
+    // "YYYY-MM-DD[ HH:MM]" cutovers, comma-separated, one per meter change in order
+    function parseCutovers(text) {
+        const dates = [];
+        const errors = [];
+        text.split(',').map(t => t.trim()).filter(Boolean).forEach(t => {
+            const dt = /^\d{4}-\d{2}-\d{2}$/.test(t) ? parseDate(`${t} 00:00`) : /^\d{4}-\d{2}-\d{2} \d{1,2}:\d{2}$/.test(t) ? parseDate(t) : null;
+            if (!dt || isNaN(dt.getTime())) errors.push(`"${t}" is not YYYY-MM-DD or YYYY-MM-DD HH:MM`);
+            else dates.push(dt);
+        });
+        return { dates, errors };
+    }
+
+    // A meter replacement leaves both meters reporting for a day or two, which would count those
+    // hours twice. Meters take over in the order their readings start, each at its cutover:
+    // the given date for that change, or else the new meter's first reading. A reading is kept
+    // only from the meter in service at its time. records must be sorted; files without a meter
+    // column, or with one meter, pass through.
+    function chooseMeters(records, cutoverDates) {
+        const firstSeen = new Map();
+        records.forEach(r => { if (r.meter && !firstSeen.has(r.meter)) firstSeen.set(r.meter, r.dt); });
+        if (firstSeen.size < 2) return { records, otherMeter: [], notes: [] };
+
+        const order = [...firstSeen.keys()]; // records are sorted, so this is by first reading
+        const cutovers = order.slice(1).map((id, i) => cutoverDates[i] || firstSeen.get(id));
+        const inService = dt => order[cutovers.filter(c => dt >= c).length];
+        const kept = [];
+        const otherMeter = [];
+        records.forEach(r => (!r.meter || r.meter === inService(r.dt) ? kept : otherMeter).push(r));
+
+        const notes = order.slice(1).map((id, i) =>
+            `Meter change: ${order[i]} to ${id} at ${cutovers[i].toLocaleString()}` +
+            (cutoverDates[i] ? ' (set under Analysis Options)' : ' (the new meter\'s first reading)'));
+        if (otherMeter.length > 0) notes.push(`${otherMeter.length} reading(s) from a meter outside its time in service left out`);
+        return { records: kept, otherMeter, notes };
+    }
+
+    // Stray dates: a run of readings separated from the rest of the file and dated before smart
+    // meters or in the future, usually a corrupted year or a 1904-based Excel date. They are set
+    // aside, or with repair on, moved by the one whole-year (or 1904-date) shift that lands them
+    // right against the rest of the data with no overlaps.
+    const PLAUSIBLE_FROM = new Date(2000, 0, 1);
+    const STRAY_GAP_DAYS = 90;
+    const EXCEL_1904_DAYS = 1462;
+
+    function setAsideStrayDates(records, repair) {
+        const clusters = [];
+        records.forEach((r, i) => {
+            if (i === 0 || r.dt - records[i - 1].dt > STRAY_GAP_DAYS * 86400000) clusters.push([]);
+            clusters[clusters.length - 1].push(r);
+        });
+        const latest = new Date(Date.now() + 86400000);
+        const plausible = c => c[0].dt >= PLAUSIBLE_FROM && c[c.length - 1].dt <= latest;
+        // With no believable cluster to compare against, leave the file alone
+        if (!clusters.some(plausible)) return { records, setAside: [], notes: [] };
+
+        const kept = clusters.filter(plausible).flat();
+        const setAside = [];
+        const notes = [];
+        clusters.filter(c => !plausible(c)).forEach(cluster => {
+            const span = `${cluster[0].dt.toLocaleDateString()} - ${cluster[cluster.length - 1].dt.toLocaleDateString()}`;
+            const shift = repair ? findDateRepair(cluster, kept, latest) : null;
+            if (shift) {
+                // The readings are real but their dates are inferred, so they count as estimated
+                cluster.forEach(r => {
+                    r.dt = shift.apply(r.dt);
+                    r.estimated = true;
+                });
+                notes.push(`Moved ${cluster.length} reading(s) dated ${span} ${shift.label} to ${cluster[0].dt.toLocaleDateString()} - ${cluster[cluster.length - 1].dt.toLocaleDateString()}`);
+                kept.push(...cluster);
+            } else {
+                setAside.push(...cluster);
+                notes.push(`Set aside ${cluster.length} reading(s) dated ${span}, far from the rest of the file` +
+                    (repair ? ' (no single date shift fits them back in)' : ' (turn on Repair stray dates to try shifting them back)'));
+            }
+        });
+        kept.sort((a, b) => a.dt - b.dt);
+        return { records: kept, setAside, notes };
+    }
+
+    function findDateRepair(cluster, kept, latest) {
+        const keptTimes = new Set(kept.map(r => r.dt.getTime()));
+        const first = cluster[0].dt;
+        const candidates = [1, -1].map(sign => ({
+            apply: dt => new Date(dt.getFullYear(), dt.getMonth(), dt.getDate() + sign * EXCEL_1904_DAYS, dt.getHours(), dt.getMinutes()),
+            label: `${sign > 0 ? 'forward' : 'back'} ${EXCEL_1904_DAYS} days (1904-based Excel dates)`
+        }));
+        const fromYear = kept[0].dt.getFullYear() - 1;
+        const toYear = kept[kept.length - 1].dt.getFullYear() + 1;
+        for (let year = fromYear; year <= toYear; year++) {
+            const n = year - first.getFullYear();
+            if (n !== 0) candidates.push({ apply: dt => addYears(dt, n), label: `${n > 0 ? 'forward' : 'back'} ${Math.abs(n)} year(s)` });
+        }
+
+        const day = 86400000;
+        const fits = candidates.filter(c => {
+            const shifted = cluster.map(r => c.apply(r.dt));
+            const start = shifted[0];
+            const end = shifted[shifted.length - 1];
+            if (start < PLAUSIBLE_FROM || end > latest) return false;
+            if (shifted.some(dt => keptTimes.has(dt.getTime()))) return false;
+            if (kept.some(r => r.dt > start && r.dt < end)) return false;
+            return kept.some(r => (r.dt < start && start - r.dt <= day) || (r.dt > end && r.dt - end <= day));
+        });
+        return fits.length === 1 ? fits[0] : null;
+    }
+
+    function summarizeSkipped(stats) {
+        const parts = Object.keys(SKIP_REASONS)
+            .filter(reason => stats.skipped[reason])
+            .map(reason => `${stats.skipped[reason]} ${SKIP_REASONS[reason]}`);
+        return parts.length > 0 ? parts.join(', ') : 'none';
+    }
+
+    // Turn parse counters into diagnostic lines plus a suggested fix
+    function describeRowStats(stats, usable = 0) {
+        const skipped = stats.skipped;
+        const lines = [
+            `Rows after header: ${stats.scanned}`,
+            `Usable rows: ${usable}; skipped: ${summarizeSkipped(stats)}`
+        ];
+
+        if (stats.firstSeen) {
+            lines.push(`Timestamps observed: ${stats.firstSeen.toLocaleString()} - ${stats.lastSeen.toLocaleString()}`);
+        }
+
+        if (stats.scanned === 0) {
+            lines.push('Suggestion: the file has a header but no data. Re-export from the Hourly Table view with a Custom Date Range.');
+        } else if (skipped.badTimestamp === stats.scanned) {
+            lines.push('Suggestion: timestamps should look like "2025-02-19 23:00" or "02/19/2025 11:00 PM". Make sure you exported the Hourly view, not Daily or Monthly, or pick the layout under Timestamp format.');
+        } else if (skipped.zero > 0 && usable === 0) {
+            lines.push('Suggestion: every reading is zero. Check that the export covers dates when the meter was active.');
+        } else if (usable > 0) {
+            lines.push('Suggestion: export a longer Custom Date Range (12 to 24 months works best).');
+        }
+
+        return lines;
+    }
+
+    function formatRange(start, end) {
+        // end is exclusive internally; show the last included day
+        const from = start ? start.toLocaleDateString() : 'start of data';
+        const to = end ? new Date(end.getFullYear(), end.getMonth(), end.getDate() - 1).toLocaleDateString() : 'end of data';
+        return `${from} - ${to}`;
+    }
+
+    // Calendar months inside the analyzed span that have no records at all
+    function findMissingMonths(records) {
+        const seen = new Set(records.map(r => `${r.dt.getFullYear()}-${r.dt.getMonth()}`));
+        const first = records[0].dt;
+        const last = records[records.length - 1].dt;
+        const missing = [];
+        for (let d = new Date(first.getFullYear(), first.getMonth(), 1); d <= last; d = new Date(d.getFullYear(), d.getMonth() + 1, 1)) {
+            if (!seen.has(`${d.getFullYear()}-${d.getMonth()}`)) {
+                missing.push(d.toLocaleDateString(undefined, { year: 'numeric', month: 'short' }));
+            }
+        }
+        return missing;
+    }
+
+    function getActiveAddons() {
+        return LOAD_ADDONS
+            .filter(a => document.getElementById(`addon-${a.key}`).checked)
+            .map(a => {
+                const values = a.inputs.map(id => Math.max(0, parseFloat(document.getElementById(id).value) || 0));
+                return { key: a.key, label: a.describe(values), hours: a.profile(values) };
+            });
+    }
+
+    // Returns new records; each reading gains the add-on draw for its share of the hour
+    function applyLoadAddons(records, addons) {
+        const fraction = (detectIntervalMinutes(records) || 60) / 60;
+        return records.map(r => {
+            const extra = addons.reduce((sum, a) => sum + a.hours[r.dt.getHours()], 0);
+            return { ...r, kwh: r.kwh + extra * fraction };
+        });
+    }
+
+    function calculateCosts(records, context) {
+        lastRecords = records;
+        const adjustments = parseBillAdjustments(document.getElementById('bill-adjustments').value);
+        billAdjustments = adjustments.items;
+
+        const { monthlyUsage, monthlyBills, totals } = calculateBills(records, { adjustments: billAdjustments });
+        lastMonthlyUsage = monthlyUsage;
+
+        const suppressed = checkDemandAssumptions(records, monthlyUsage);
+        const compliance = scoreLoadShifting(records);
+        const sensitivity = scoreBoundarySensitivity(records);
+        const history = parseEnrollmentHistory(document.getElementById('enrollment-history').value);
+        const actual = history.periods.length > 0 || history.errors.length > 0
+            ? billEnrollmentHistory(records, history, { adjustments: billAdjustments })
+            : null;
+        const cop = parseFloat(document.getElementById('heating-cop').value);
+        const heating = cop > 0 ? heatingCostPerMMBtu(monthlyUsage, monthlyBills, cop) : null;
+        const sqft = parseFloat(document.getElementById('home-sqft').value) || 0;
+        const occupants = parseFloat(document.getElementById('home-occupants').value) || 0;
+        const home = sqft > 0 || occupants > 0
+            ? normalizeByHome(totals, records.reduce((sum, r) => sum + r.kwh, 0), context.duration, sqft, occupants)
+            : null;
+        const flatPremium = document.getElementById('flatbill-premium').value;
+        const flatBill = flatPremium !== '' ? estimateFlatBill(monthlyUsage, monthlyBills, parseFloat(flatPremium) || 0) : null;
+        const stressDays = parseInt(document.getElementById('stress-days').value, 10) || 0;
+        const stressFactor = parseFloat(document.getElementById('stress-factor').value) || 1;
+        const stress = stressDays > 0 ? runHeatWaveStress(records, stressDays, stressFactor, totals) : null;
+        const stabilitySamples = Math.min(500, parseInt(document.getElementById('stability-samples').value, 10) || 0);
+        const stability = stabilitySamples > 0 ? runRankStability(records, stabilitySamples, suppressed) : null;
+        const billed = parseBilledUsage(document.getElementById('billed-usage').value);
+        const meterCheck = billed.items.length > 0 || billed.errors.length > 0
+            ? {
+                rows: checkBilledUsage(billed.items, monthlyUsage, parseFloat(document.getElementById('billed-tolerance').value) || 0),
+                errors: billed.errors
+            }
+            : null;
+        const significant = parseSignificantDates(document.getElementById('significant-dates').value);
+        const events = significant.events.length > 0 || significant.errors.length > 0
+            ? { items: compareAroundDates(significant.events, monthlyUsage, monthlyBills), errors: significant.errors }
+            : null;
+        const rateChanges = loadedRates
+            ? {
+                name: loadedRates.name,
+                changes: diffRates(defaultRates(), loadedRates.config),
+                before: withRates(defaultRates(), () => calculateBills(records, { adjustments: billAdjustments }).totals),
+                after: totals
+            }
+            : null;
+
+        const commitments = {};
+        PLAN_IDS.forEach(id => {
+            commitments[id] = checkTermCommitment(id, monthlyBills);
+        });
+        const minSummerDays = parseInt(document.getElementById('min-summer-days').value, 10);
+        const summer = checkSummerCoverage(monthlyUsage, isNaN(minSummerDays) ? MIN_SUMMER_DAYS : minSummerDays);
+        monthConfidence = rateMonthConfidence(records, context.rowStats.conflicts, context.coverage.gaps);
+        const timeline = Object.keys(monthlyBills).map(key => {
+            const [y, m] = key.split('-').map(Number);
+            return {
+                key,
+                bills: monthlyBills[key],
+                days: monthlyUsage[key].days.size,
+                partial: monthlyUsage[key].days.size < daysInMonth(y, m - 1),
+                confidence: monthConfidence[key]
+            };
+        });
+
+        // Display
+        displayResults({
+            plans: totals,
+            commitments,
+            summer,
+            suppressed,
+            compliance,
+            sensitivity,
+            heating,
+            home,
+            flatBill,
+            actual,
+            stress,
+            stability,
+            events,
+            meterCheck,
+            rateChanges,
+            timeline,
+            stats: {
+                start: records[0].dt,
+                end: records[records.length - 1].dt,
+                duration: context.duration,
+                totalUsage: records.reduce((sum, r) => sum + r.kwh, 0),
+                note: adjustments.errors.length > 0
+                    ? `${context.note} Ignored bill adjustment line(s): ${adjustments.errors.join('; ')}.`
+                    : context.note,
+                coverage: context.coverage,
+                rowStats: context.rowStats,
+                clock: context.clock
+            }
+        });
+    }
+
+    // "YYYY-MM kWh" lines copied from bills, e.g. "2024-07 1523"
+    function parseBilledUsage(text) {
+        const items = [];
+        const errors = [];
+        text.split('\n').map(line => line.trim()).filter(Boolean).forEach(line => {
+            const match = line.match(/^(\d{4}-\d{2})\s+([\d,]*\.?\d+)\s*(?:kwh)?$/i);
+            if (!match) {
+                errors.push(line);
+                return;
+            }
+            items.push({ key: match[1], kwh: parseFloat(match[2].replace(/,/g, '')) });
+        });
+        items.sort((a, b) => a.key.localeCompare(b.key));
+        return { items, errors };
+    }
+
+    // Interval kWh against the utility's billed kWh for each month. status is 'ok' within
+    // tolerancePct, 'low' when readings are missing (the usual AMI gap), 'high' when the file
+    // has more than was billed, or 'no-data' for a month outside the analyzed range.
+    function checkBilledUsage(items, monthlyUsage, tolerancePct) {
+        return items.map(({ key, kwh }) => {
+            const usage = monthlyUsage[key];
+            if (!usage) return { key, billed: kwh, interval: null, diffPct: null, status: 'no-data' };
+            const diffPct = kwh > 0 ? (usage.total - kwh) / kwh * 100 : 0;
+            const status = Math.abs(diffPct) <= tolerancePct ? 'ok' : diffPct < 0 ? 'low' : 'high';
+            return { key, billed: kwh, interval: usage.total, diffPct, status };
+        });
+    }
+
+    // Whole months compared on each side of a significant date
+    const EVENT_WINDOW_MONTHS = 3;
+
+    // "YYYY-MM-DD label" lines for life events (move-in, EV purchase, solar turned on)
+    function parseSignificantDates(text) {
+        const events = [];
+        const errors = [];
+        text.split('\n').map(line => line.trim()).filter(Boolean).forEach(line => {
+            const match = line.match(/^(\d{4})-(\d{2})-(\d{2})\s+(.+)$/);
+            if (!match) {
+                errors.push(line);
+                return;
+            }
+            events.push({ key: `${match[1]}-${match[2]}-${match[3]}`, label: match[4].trim() });
+        });
+        events.sort((a, b) => a.key.localeCompare(b.key));
+        return { events, errors };
+    }
+
+    // Usage and bills before and after each date, from up to EVENT_WINDOW_MONTHS whole billed
+    // months on either side. The month a date falls in mixes both and is left out, unless the
+    // date is its 1st. Bills are scaled to 30 days so partial months compare fairly.
+    function compareAroundDates(events, monthlyUsage, monthlyBills) {
+        const keys = Object.keys(monthlyUsage).sort();
+        const summarize = months => {
+            if (months.length === 0) return null;
+            const days = months.reduce((sum, k) => sum + monthlyUsage[k].days.size, 0);
+            const kwh = months.reduce((sum, k) => sum + monthlyUsage[k].total, 0);
+            const per30 = {};
+            PLAN_IDS.forEach(id => {
+                per30[id] = months.reduce((sum, k) => sum + monthlyBills[k][id].total, 0) / days * 30;
+            });
+            return { from: months[0], to: months[months.length - 1], kwhPerDay: kwh / days, per30 };
+        };
+        return events.map(event => {
+            const month = event.key.slice(0, 7);
+            const startsMonth = event.key.endsWith('-01');
+            const before = keys.filter(k => k < month).slice(-EVENT_WINDOW_MONTHS);
+            const after = keys.filter(k => k > month || (startsMonth && k === month)).slice(0, EVENT_WINDOW_MONTHS);
+            return { ...event, before: summarize(before), after: summarize(after) };
+        });
+    }
+
+    // Heat-wave stress test: the N highest-usage summer days (the best stand-in for the
+    // hottest without weather data) are scaled by factor and every plan is billed again
+    function runHeatWaveStress(records, days, factor, baseTotals) {
+        const dayKey = dt => `${dt.getFullYear()}-${dt.getMonth()}-${dt.getDate()}`;
+        const dailyKwh = new Map();
+        records.filter(r => inSeason(FCR_SEASON, r.dt)).forEach(r => {
+            dailyKwh.set(dayKey(r.dt), (dailyKwh.get(dayKey(r.dt)) || 0) + r.kwh);
+        });
+        const hotDays = new Set([...dailyKwh.entries()].sort((a, b) => b[1] - a[1]).slice(0, days).map(([k]) => k));
+        if (hotDays.size === 0) return { days: 0, factor, plans: {} };
+
+        const stressed = records.map(r => hotDays.has(dayKey(r.dt)) ? { ...r, kwh: r.kwh * factor } : r);
+        const { totals } = calculateBills(stressed, { adjustments: billAdjustments });
+        const rank = t => [...PLAN_IDS].sort((a, b) => t[a].total - t[b].total);
+        const before = rank(baseTotals);
+        const after = rank(totals);
+
+        const plans = {};
+        PLAN_IDS.forEach(id => {
+            plans[id] = {
+                base: baseTotals[id].total,
+                stressed: totals[id].total,
+                rankBefore: before.indexOf(id) + 1,
+                rankAfter: after.indexOf(id) + 1
+            };
+        });
+        return { days: hotDays.size, factor, plans };
+    }
+
+    // Rank stability: bootstrapped years where every day's usage is swapped for that of a random
+    // day from the same month and day type (weekday, or weekend and observed holiday, which the
+    // TOU windows treat alike), each billed and ranked like the real data. A swapped-in day falls
+    // in the same TOU periods and season, so each day is aggregated once and samples are put
+    // together from those daily buckets. The generator is seeded, so the same data and options
+    // give the same answer.
+    function runRankStability(records, samples, suppressed) {
+        // The same pool as the recommendation: plans with unreliable demand data sit out
+        const pool = getSelectedPlans();
+        const selected = pool.some(id => !suppressed[id]) ? pool.filter(id => !suppressed[id]) : pool;
+        const pad = n => String(n).padStart(2, '0');
+        const byDay = new Map(); // "YYYY-MM-DD" -> records
+        records.forEach(r => {
+            const key = `${r.dt.getFullYear()}-${pad(r.dt.getMonth() + 1)}-${pad(r.dt.getDate())}`;
+            if (!byDay.has(key)) byDay.set(key, []);
+            byDay.get(key).push(r);
+        });
+        const days = [...byDay.entries()].map(([key, dayRecords]) => {
+            const dt = dayRecords[0].dt;
+            const weekend = dt.getDay() === 0 || dt.getDay() === 6 || HOLIDAYS.includes(key);
+            return { key, monthKey: key.slice(0, 7), pool: `${dt.getMonth()} ${weekend}`, usage: aggregateMonthly(dayRecords)[key.slice(0, 7)] };
+        });
+        const pools = new Map();
+        days.forEach(day => {
+            if (!pools.has(day.pool)) pools.set(day.pool, []);
+            pools.get(day.pool).push(day);
+        });
+
+        // mulberry32
+        let seed = 0x5eed;
+        const random = () => {
+            seed = (seed + 0x6d2b79f5) | 0;
+            let t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+            t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+            return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+        };
+
+        // Monthly buckets in the aggregateMonthly() layout, from one source day per target day
+        const assemble = pick => {
+            const monthly = {};
+            days.forEach(day => {
+                const src = pick(day).usage;
+                if (!monthly[day.monthKey]) {
+                    const plans = {};
+                    PLAN_IDS.forEach(id => {
+                        plans[id] = { periods: {}, summerKwh: 0, summerDays: new Set(), demandKw: 0 };
+                    });
+                    monthly[day.monthKey] = { total: 0, days: new Set(), fcr: 0, plans };
+                }
+                const m = monthly[day.monthKey];
+                m.total += src.total;
+                m.days.add(day.key);
+                m.fcr += src.fcr;
+                PLAN_IDS.forEach(id => {
+                    const from = src.plans[id];
+                    const to = m.plans[id];
+                    Object.entries(from.periods).forEach(([period, kwh]) => { to.periods[period] = (to.periods[period] || 0) + kwh; });
+                    to.summerKwh += from.summerKwh;
+                    if (from.summerDays.size > 0) to.summerDays.add(day.key);
+                    to.demandKw = Math.max(to.demandKw, from.demandKw);
+                });
+            });
+            return monthly;
+        };
+
+        const winnerOf = monthly => {
+            const monthlyBills = {};
+            Object.keys(monthly).sort().forEach(key => {
+                monthlyBills[key] = billMonth(key, monthly[key], { adjustments: billAdjustments });
+            });
+            const total = id => Object.values(monthlyBills).reduce((sum, bills) => sum + bills[id].total, 0);
+            const ranked = [...selected].sort((a, b) => total(a) - total(b));
+            return ranked.find(id => checkTermCommitment(id, monthlyBills).eligible) || ranked[0];
+        };
+
+        const wins = {};
+        selected.forEach(id => { wins[id] = 0; });
+        for (let n = 0; n < samples; n++) {
+            wins[winnerOf(assemble(day => {
+                const pool = pools.get(day.pool);
+                return pool[Math.floor(random() * pool.length)];
+            }))]++;
+        }
+        return { samples, asUsed: winnerOf(assemble(day => day)), wins };
+    }
 
-            // Look for "Hour" and "kWh" (case insensitive)
-            const hourIdx = row.findIndex(c => c && c.toString().toLowerCase().includes('hour')); // "Hour" or "Usage Hour"
-            const kwhIdx = row.findIndex(c => c && c.toString().toLowerCase().includes('kwh')); // "kWh" or "Usage Amount"
+    // Service territory centre (Atlanta), for solar noon
+    const SITE_LONGITUDE = -84.39;
 
-            if (hourIdx !== -1 && kwhIdx !== -1) {
-                headerRowIndex = i;
-                colMap.timestamp = hourIdx;
-                colMap.kwh = kwhIdx;
-                break;
-            }
-        }
+    // Solar noon in local clock minutes after midnight (NOAA equation-of-time approximation)
+    function solarNoonMinutes(dt) {
+        const dayOfYear = (Date.UTC(dt.getFullYear(), dt.getMonth(), dt.getDate()) - Date.UTC(dt.getFullYear(), 0, 0)) / 86400000;
+        const b = 2 * Math.PI * (dayOfYear - 81) / 365;
+        const equationOfTime = 9.87 * Math.sin(2 * b) - 7.53 * Math.cos(b) - 1.5 * Math.sin(b);
+        return 720 - 4 * SITE_LONGITUDE - equationOfTime - dt.getTimezoneOffset();
+    }
 
-        if (headerRowIndex === -1) {
-            throw new Error('Could not find "Hour" and "kWh" columns in the first 20 rows.');
+    // Homes with solar show a midday dip in grid usage centred on solar noon, halfway
+    // between sunrise and sunset. If the dip sits well away from where the sun puts it,
+    // the timestamps are probably offset. Without a dip there is nothing to compare.
+    function detectClockSkew(records) {
+        const sums = new Array(24).fill(0);
+        const counts = new Array(24).fill(0);
+        let noonSum = 0;
+        records.forEach(r => {
+            const h = r.dt.getHours();
+            sums[h] += r.kwh;
+            counts[h]++;
+            noonSum += solarNoonMinutes(r.dt);
+        });
+        const shape = sums.map((sum, h) => counts[h] > 0 ? sum / counts[h] : 0);
+        const typical = median(shape);
+        const middayMin = Math.min(...shape.slice(9, 17));
+        if (typical <= 0 || middayMin > 0.3 * typical) {
+            return { detected: false, reason: 'no solar dip in the daily load shape to check against' };
         }
 
-        let records = [];
+        // Centroid of the dip below the typical hour, at the middle of each hour
+        let weight = 0;
+        let moment = 0;
+        for (let h = 7; h <= 19; h++) {
+            const deficit = Math.max(0, typical - shape[h]);
+            weight += deficit;
+            moment += deficit * (h * 60 + 30);
+        }
+        const dipCentre = moment / weight;
+        const solarNoon = noonSum / records.length;
+        const suggested = Math.round((solarNoon - dipCentre) / 15) * 15;
+        return {
+            detected: Math.abs(suggested) >= 30,
+            suggested,
+            reason: `solar dip centred at ${formatClock(dipCentre)} vs solar noon ${formatClock(solarNoon)}`
+        };
+    }
 
-        for (let i = headerRowIndex + 1; i < rows.length; i++) {
-            const row = rows[i];
-            if (!row || row.length <= Math.max(colMap.timestamp, colMap.kwh)) continue;
+    function formatClock(minutes) {
+        const m = Math.round(minutes);
+        return `${Math.floor(m / 60)}:${String(m % 60).padStart(2, '0')}`;
+    }
 
-            const timestampStr = row[colMap.timestamp];
-            const kwhVal = row[colMap.kwh];
+    const BTU_PER_KWH = 3412.14;
+
+    // Winter-month (outside R-30's summer, Oct-May) cost per MMBtu of heat delivered by
+    // a heat pump with the given COP, for comparing against a gas bill. Fixed charges are
+    // left out because they are owed whether or not the heat pump runs.
+    function heatingCostPerMMBtu(monthlyUsage, monthlyBills, cop) {
+        const winterKeys = Object.keys(monthlyUsage).filter(key => monthlyUsage[key].plans['r30'].summerKwh === 0);
+        const kwh = winterKeys.reduce((sum, key) => sum + monthlyUsage[key].total, 0);
+        if (kwh === 0) return { cop, kwh, plans: {} };
+
+        const mmbtu = kwh * BTU_PER_KWH * cop / 1e6;
+        const plans = {};
+        PLAN_IDS.forEach(id => {
+            const variable = winterKeys.reduce((sum, key) => {
+                const bill = monthlyBills[key][id];
+                return sum + bill.total - bill.breakdown.fixed * TAX_RATE;
+            }, 0);
+            plans[id] = { perKwh: variable / kwh, perMMBtu: variable / mmbtu };
+        });
+        return { cop, kwh, plans };
+    }
 
-            if (timestampStr === undefined || timestampStr === null) continue;
+    // Usage and each plan's total per square foot and per occupant, annualized from the analyzed
+    // days, so homes of different sizes (or a landlord's units) can be compared. sqft or
+    // occupants may be 0 when not given.
+    function normalizeByHome(totals, kwh, days, sqft, occupants) {
+        const years = days / 365;
+        const per = (amount, divisor) => (divisor > 0 ? amount / years / divisor : null);
+        const plans = {};
+        PLAN_IDS.forEach(id => {
+            plans[id] = { perSqftYear: per(totals[id].total, sqft), perPersonYear: per(totals[id].total, occupants) };
+        });
+        return { sqft, occupants, days, kwhPerSqftYear: per(kwh, sqft), kwhPerPersonYear: per(kwh, occupants), plans };
+    }
 
-            let kwh = parseFloat(kwhVal);
-            if (isNaN(kwh)) continue;
+    // Months a FlatBill payment is levelized over
+    const FLATBILL_MONTHS = 12;
 
-            // Filter out zero usage
-            if (kwh <= 0.001) continue;
+    // FlatBill-style fixed payment: the R-30 bills of the last FLATBILL_MONTHS complete
+    // months, averaged, plus premiumPct for the risk the utility takes on. Each plan's bills
+    // over the same months show how much a fixed payment smooths out.
+    function estimateFlatBill(monthlyUsage, monthlyBills, premiumPct) {
+        const complete = Object.keys(monthlyUsage).sort().filter(key => {
+            const [year, month] = key.split('-').map(Number);
+            return monthlyUsage[key].days.size === daysInMonth(year, month - 1);
+        });
+        const keys = complete.slice(-FLATBILL_MONTHS);
+        if (keys.length === 0) return { premiumPct, keys, plans: {} };
+
+        const plans = {};
+        PLAN_IDS.forEach(id => {
+            const totals = keys.map(key => monthlyBills[key][id].total);
+            plans[id] = {
+                annual: totals.reduce((sum, t) => sum + t, 0) / keys.length * 12,
+                low: Math.min(...totals),
+                high: Math.max(...totals)
+            };
+        });
+        const monthly = roundCents(plans['r30'].annual / 12 * (1 + premiumPct / 100));
+        return { premiumPct, keys, monthly, annual: monthly * 12, plans };
+    }
 
-            let dt = null;
-            if (typeof timestampStr === 'number') {
-                // Excel serial date
-                const dateObj = new Date((timestampStr - 25569) * 86400 * 1000);
-                dt = new Date(dateObj.getUTCFullYear(), dateObj.getUTCMonth(), dateObj.getUTCDate(), dateObj.getUTCHours(), dateObj.getUTCMinutes());
-            } else {
-                dt = parseDate(timestampStr.toString());
-            }
+    // Readings this far above the day's typical draw look like an EV, water
+    // heater or pool pump running rather than background load
+    const SHIFTABLE_MIN_KW = 1.5;
 
-            if (dt) {
-                records.push({ dt, kwh });
-            }
-        }
+    // How much of the shiftable-looking load already sits in each plan's
+    // cheapest period; the rest is what automation could still move
+    function scoreLoadShifting(records) {
+        const fraction = (detectIntervalMinutes(records) || 60) / 60;
+        const byDay = new Map();
+        records.forEach(r => {
+            const day = r.dt.toDateString();
+            if (!byDay.has(day)) byDay.set(day, []);
+            byDay.get(day).push(r);
+        });
 
-        if (records.length === 0) {
-            throw new Error('No valid records found (all zero or invalid).');
-        }
+        let shiftableKwh = 0;
+        const inWindow = {};
+        PLAN_IDS.forEach(id => { inWindow[id] = 0; });
+        byDay.forEach(dayRecords => {
+            const baseKw = median(dayRecords.map(r => r.kwh / fraction));
+            dayRecords.forEach(r => {
+                const excessKw = r.kwh / fraction - baseKw;
+                if (excessKw < SHIFTABLE_MIN_KW) return;
+                const kwh = excessKw * fraction;
+                shiftableKwh += kwh;
+                PLAN_IDS.forEach(id => {
+                    const period = RATE_PLANS[id].cheapestPeriod;
+                    if (period && classifyHour(id, r.dt) === period) inWindow[id] += kwh;
+                });
+            });
+        });
 
-        // Sort by date ascending
-        records.sort((a, b) => a.dt - b.dt);
+        const plans = {};
+        PLAN_IDS.filter(id => RATE_PLANS[id].cheapestPeriod).forEach(id => {
+            plans[id] = {
+                inWindow: inWindow[id],
+                headroom: shiftableKwh - inWindow[id],
+                score: shiftableKwh > 0 ? inWindow[id] / shiftableKwh : null
+            };
+        });
+        return { shiftableKwh, totalKwh: records.reduce((sum, r) => sum + r.kwh, 0), plans };
+    }
 
-        // --- Date Range Logic ---
-        // 1. Check total duration
-        const startDt = records[0].dt;
-        const endDt = records[records.length - 1].dt;
-        const durationMs = endDt - startDt;
-        const durationDays = durationMs / (1000 * 60 * 60 * 24);
-
-        if (durationDays < 30) {
-            showError(`Insufficient data: ${durationDays.toFixed(1)} days found. At least 30 days are required for an accurate recommendation.`);
-            return;
-        }
+    const BOUNDARY_WINDOW_MIN = 15;
+
+    // Energy within 15 minutes of a TOU period change, and how far the bill could move
+    // if that energy landed on the other side (clock skew, or start- vs end-of-interval
+    // timestamps). Readings are assumed flat across their interval.
+    function scoreBoundarySensitivity(records) {
+        const intervalMin = detectIntervalMinutes(records) || 60;
+        const nearShare = Math.min(BOUNDARY_WINDOW_MIN, intervalMin) / intervalMin;
+        const intervalMs = intervalMin * 60 * 1000;
+        const windowMs = BOUNDARY_WINDOW_MIN * 60 * 1000;
+
+        const plans = {};
+        PLAN_IDS.filter(id => id !== 'r30').forEach(id => {
+            const rates = ENERGY_RATES[id];
+            let nearKwh = 0;
+            let swing = 0;
+            records.forEach(r => {
+                const period = classifyHour(id, r.dt);
+                const before = classifyHour(id, new Date(r.dt.getTime() - windowMs));
+                const after = classifyHour(id, new Date(r.dt.getTime() + intervalMs));
+                [before, after].forEach(other => {
+                    if (other === period) return;
+                    const kwh = r.kwh * nearShare;
+                    nearKwh += kwh;
+                    swing += kwh * Math.abs(rates[period] - rates[other]) * TAX_RATE;
+                });
+            });
+            plans[id] = { nearKwh, swing };
+        });
+        return { windowMin: BOUNDARY_WINDOW_MIN, totalKwh: records.reduce((sum, r) => sum + r.kwh, 0), plans };
+    }
 
-        // 2. Truncate to most recent full years if > 1 year
-        let usedRecords = records;
-        let note = "";
+    // Short description, eligibility and schedule reference under each plan name
+    function renderPlanGlossary() {
+        PLAN_IDS.forEach(id => {
+            const plan = RATE_PLANS[id];
+            const card = document.getElementById(`card-${id}`);
+            card.insertAdjacentHTML('beforeend', `<div class="plan-glossary">` +
+                `<p>${plan.description}</p>` +
+                `<p class="plan-eligibility">${plan.eligibility}</p>` +
+                `<pre class="schedule-grid">${renderScheduleGrid(id)}</pre>` +
+                `<a href="${plan.reference}" target="_blank">Schedule ${plan.schedule}</a>` +
+                `</div>`);
+        });
+    }
 
-        if (durationDays >= 365) {
-            const fullYears = Math.floor(durationDays / 365);
-            const targetDays = fullYears * 365;
-            const cutoffDate = new Date(endDt.getTime() - (targetDays * 24 * 60 * 60 * 1000));
-            usedRecords = records.filter(r => r.dt >= cutoffDate);
-            note = `Using most recent ${fullYears} full year(s) of data for accurate seasonal comparison.`;
-        } else {
-            note = "Less than 1 year of data. Seasonal variations may affect accuracy.";
+    // 7-day x 24-hour text grid of each plan's periods, for a summer and a winter week
+    function renderScheduleGrid(planId) {
+        if (planId === 'r30') {
+            return 'No time periods: price depends on\nmonthly kWh block, not the hour.';
         }
 
-        // Re-calculate stats for used records
-        const effectiveStart = usedRecords[0].dt;
-        const effectiveEnd = usedRecords[usedRecords.length - 1].dt;
-        const effectiveDuration = (effectiveEnd - effectiveStart) / (1000 * 60 * 60 * 24);
-
-        // Check for gaps
-        let gapWarnings = 0;
-        for (let i = 0; i < usedRecords.length - 1; i++) {
-            const diffMs = usedRecords[i + 1].dt - usedRecords[i].dt;
-            const diffMins = diffMs / (1000 * 60);
-            if (diffMins > 90) {
-                gapWarnings++;
-            }
-        }
+        const SYMBOLS = { on_peak: '#', off_peak: '-', super_off_peak: '.' };
+        const DAY_NAMES = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
+        const axis = '    0     6     12    18';
+        // Reference weeks starting on a Monday, clear of holidays
+        const summer = RATE_PLANS[planId].summer;
+        const seasons = [
+            { label: `Summer (${summer.from} to ${summer.to})`, monday: new Date(2024, 6, 8) },
+            { label: 'Winter (rest of year)', monday: new Date(2024, 0, 8) }
+        ];
 
-        if (gapWarnings > 50) {
-            console.warn(`Detected ${gapWarnings} gaps > 90 mins.`);
-        }
+        const used = new Set();
+        const blocks = seasons.map(season => {
+            const lines = [season.label, axis];
+            DAY_NAMES.forEach((dayName, dayIndex) => {
+                let row = '';
+                for (let hour = 0; hour < 24; hour++) {
+                    const dt = new Date(season.monday.getFullYear(), season.monday.getMonth(), season.monday.getDate() + dayIndex, hour);
+                    const period = classifyHour(planId, dt);
+                    used.add(period);
+                    row += SYMBOLS[period];
+                }
+                lines.push(`${dayName} ${row}`);
+            });
+            return lines.join('\n');
+        });
 
-        calculateCosts(usedRecords, effectiveDuration, note);
+        const legend = Object.keys(SYMBOLS)
+            .filter(period => used.has(period))
+            .map(period => `${SYMBOLS[period]} ${period.replace(/_/g, '-')}`)
+            .join('  ');
+        return blocks.join('\n\n') + '\n\n' + legend;
     }
 
-    function parseDate(str) {
-        // "2025-02-19 23:00"
-        const [datePart, timePart] = str.split(' ');
-        if (!datePart || !timePart) return null;
-        const [y, m, d] = datePart.split('-').map(Number);
-        const [hr, min] = timePart.split(':').map(Number);
-        return new Date(y, m - 1, d, hr, min);
-    }
+    // --- Dry Run ---
 
-    function calculateCosts(records, durationDays, note) {
-        // Constants for Riders & Taxes
-        const FCR_SUMMER = 0.045876; // ~4.6 cents/kWh (Jun-Sep)
-        const FCR_WINTER = 0.042859; // ~4.3 cents/kWh (Oct-May)
-        const TAX_RATE = 1.12;       // ~12% for NCCR, ECC, Franchise Fee, Sales Tax
+    // Parse facts only: what was read and how it looks, without computing any bills
+    function renderValidation(records, rowStats) {
+        resultsSection.classList.add('hidden');
 
-        // Aggregates
-        let agg_tou_reo_on = 0;
-        let agg_tou_reo_off = 0;
-        let agg_tou_oa_on = 0;
-        let agg_tou_oa_off = 0;
-        let agg_tou_oa_super = 0;
+        const intervalMins = detectIntervalMinutes(records);
+        const medianKwh = median(records.map(r => r.kwh));
+        // Residential hourly readings rarely exceed ~10 kWh; values 1000x that suggest Wh
+        const perHour = intervalMins ? medianKwh * 60 / intervalMins : medianKwh;
+        const unitGuess = perHour > 100
+            ? `Possibly Wh, not kWh (median ${medianKwh.toFixed(1)} per interval)`
+            : `kWh (median ${medianKwh.toFixed(3)} per interval)`;
+
+        const formats = Object.entries(rowStats.formats).map(([f, n]) => `${f} (${n})`).join(', ');
+        const missing = findMissingMonths(records);
+
+        const lines = [
+            ['Header row', rowStats.headerRow],
+            ['Rows after header', rowStats.scanned],
+            ['Usable rows', records.length],
+            ['Skipped', summarizeSkipped(rowStats)],
+            ['Interval length', intervalMins ? `${intervalMins} minutes` : 'unknown (single row)'],
+            ['Timestamp format', formats || 'none'],
+            ['Unit', unitGuess],
+            ['Coverage', `${records[0].dt.toLocaleString()} - ${records[records.length - 1].dt.toLocaleString()}`],
+            ['Missing months', missing.length > 0 ? missing.join(', ') : 'none'],
+            ['Missing readings', rowStats.gaps.gaps.length > 0
+                ? `${describeMissing(rowStats.gaps)}: ${Object.keys(rowStats.gaps.months).sort().map(key => `${key} ${formatHours(rowStats.gaps.months[key].missing * rowStats.gaps.minutes / 60)}`).join(', ')}`
+                : 'none'],
+            ['Gap filling', rowStats.gapFill
+                ? `${rowStats.gapFill.readings} reading(s) would be filled ${GAP_FILL_METHODS[rowStats.gapFill.method]}`
+                : 'off, gaps are left open'],
+            ['Stray dates', rowStats.strayDates.length > 0 ? rowStats.strayDates.join('; ') : 'none'],
+            ['Meter changes', rowStats.meterChanges.length > 0 ? escapeHtml(rowStats.meterChanges.join('; ')) : 'none'],
+            ['File overlap', describeFileOverlap(rowStats) || 'none'],
+            ['Duplicates', describeDuplicates(rowStats) || 'none'],
+            ['Time zone', describeTimeZone(rowStats)],
+            ['Interval label', describeIntervalLabel(rowStats.intervalLabel)],
+            ['Pipeline', rowStats.pipeline ? escapeHtml(`${rowStats.pipeline.name}: ${describePipeline(rowStats.pipeline).join('; ')}`) : 'none']
+        ];
 
-        // Monthly tracking for R-30 and Demand
-        const monthlyUsage = {}; // "YYYY-MM" -> { total: 0, days: Set(dayStr), maxDemand: 0, fcr: 0 }
+        document.getElementById('validation-details').innerHTML = lines
+            .map(([label, value]) => `<p>${label}: <span>${value}</span></p>`)
+            .join('');
+        renderSkipSamples(document.getElementById('validation-samples'), rowStats);
+        validationSection.classList.remove('hidden');
+    }
 
-        // FCR Accumulator
-        let total_fcr = 0;
+    // --- Hourly Price Export ---
 
+    // One year of hours on the Georgia clock from the first reading's hour, both passes through
+    // the hour clocks repeat in November included. Usage is bucketed by clock hour, so each
+    // month's R-30 block position matches the bill.
+    function buildPriceCurve(planId, records) {
+        const HOUR_MS = 60 * 60 * 1000;
+        const hourKey = (dt, repeated) => `${dt.getFullYear()}-${dt.getMonth()}-${dt.getDate()} ${dt.getHours()}${repeated ? ' repeated' : ''}`;
+        const usageByHour = new Map();
         records.forEach(r => {
-            const dt = r.dt;
-            const kwh = r.kwh;
-            const month = dt.getMonth() + 1; // 1-12
-            const monthKey = `${dt.getFullYear()}-${String(month).padStart(2, '0')}`;
-            const dayKey = `${monthKey}-${String(dt.getDate()).padStart(2, '0')}`;
-
-            // FCR Calculation
-            const isSummer = month >= 6 && month <= 9;
-            const fcrRate = isSummer ? FCR_SUMMER : FCR_WINTER;
-            const fcrCost = kwh * fcrRate;
-            total_fcr += fcrCost;
-
-            // Initialize monthly bucket
-            if (!monthlyUsage[monthKey]) {
-                monthlyUsage[monthKey] = { total: 0, days: new Set(), maxDemand: 0 };
-            }
-            monthlyUsage[monthKey].total += kwh;
-            monthlyUsage[monthKey].days.add(dayKey);
-            if (kwh > monthlyUsage[monthKey].maxDemand) {
-                monthlyUsage[monthKey].maxDemand = kwh;
-            }
-
-            // Classify
-            if (isOnPeak(dt)) {
-                agg_tou_reo_on += kwh;
-            } else {
-                agg_tou_reo_off += kwh;
-            }
-
-            const oaPeriod = getTouOaPeriod(dt);
-            if (oaPeriod === 'on_peak') agg_tou_oa_on += kwh;
-            else if (oaPeriod === 'super_off_peak') agg_tou_oa_super += kwh;
-            else agg_tou_oa_off += kwh;
+            const key = hourKey(r.dt, r.repeated);
+            usageByHour.set(key, (usageByHour.get(key) || 0) + r.kwh);
         });
 
-        // Billing days calculation
-        const allDays = new Set();
-        records.forEach(r => {
-            const dayKey = `${r.dt.getFullYear()}-${r.dt.getMonth()}-${r.dt.getDate()}`;
-            allDays.add(dayKey);
-        });
-        const billingDays = allDays.size;
-
-        // --- Costs (Base + FCR) * Tax ---
-
-        // 1. TOU-REO
-        const tou_reo_fixed = 0.4603 * billingDays;
-        const cost_reo_on = agg_tou_reo_on * 0.297868;
-        const cost_reo_off = agg_tou_reo_off * 0.076281;
-        const tou_reo_energy = cost_reo_on + cost_reo_off;
-        const tou_reo_total = (tou_reo_fixed + tou_reo_energy + total_fcr) * TAX_RATE;
-
-        // 2. TOU-OA
-        const tou_oa_fixed = 0.4603 * billingDays;
-        const cost_oa_on = agg_tou_oa_on * 0.297868;
-        const cost_oa_off = agg_tou_oa_off * 0.101676;
-        const cost_oa_super = agg_tou_oa_super * 0.021859;
-        const tou_oa_energy = cost_oa_on + cost_oa_off + cost_oa_super;
-        const tou_oa_total = (tou_oa_fixed + tou_oa_energy + total_fcr) * TAX_RATE;
-
-        // 3. TOU-RD
-        const tou_rd_fixed = 0.4603 * billingDays;
-        const cost_rd_on = agg_tou_reo_on * 0.142986;
-        const cost_rd_off = agg_tou_reo_off * 0.015288;
-        const tou_rd_energy = cost_rd_on + cost_rd_off;
-        let total_demand_charge = 0;
-        Object.values(monthlyUsage).forEach(m => {
-            total_demand_charge += m.maxDemand * 12.21;
-        });
-        const tou_rd_total = (tou_rd_fixed + tou_rd_energy + total_demand_charge + total_fcr) * TAX_RATE;
-
-        // 4. R-30
-        let r30_base_total = 0;
-        let cost_r30_summer_tier1 = 0;
-        let cost_r30_summer_tier2 = 0;
-        let cost_r30_summer_tier3 = 0;
-        let cost_r30_winter = 0;
-
-        Object.keys(monthlyUsage).forEach(key => {
-            const [year, month] = key.split('-').map(Number); // month is 1-based
-            const usage = monthlyUsage[key].total;
-            const days = monthlyUsage[key].days.size;
-
-            const fixed = 0.4603 * days;
-            let energyCost = 0;
-
-            if (month >= 6 && month <= 9) { // Summer
-                const tier1 = Math.min(usage, 650);
-                const tier2 = usage > 650 ? Math.min(usage - 650, 350) : 0;
-                const tier3 = usage > 1000 ? usage - 1000 : 0;
-
-                const c1 = tier1 * 0.086121;
-                const c2 = tier2 * 0.143047;
-                const c3 = tier3 * 0.148051;
-
-                cost_r30_summer_tier1 += c1;
-                cost_r30_summer_tier2 += c2;
-                cost_r30_summer_tier3 += c3;
-
-                energyCost = c1 + c2 + c3;
-            } else { // Winter
-                const cWinter = usage * 0.080602;
-                cost_r30_winter += cWinter;
-                energyCost = cWinter;
-            }
-            r30_base_total += fixed + energyCost;
-        });
-        const r30_total = (r30_base_total + total_fcr) * TAX_RATE;
-
-        // Display
-        displayResults({
-            tou_reo: {
-                total: tou_reo_total,
-                breakdown: {
-                    fixed: tou_reo_fixed,
-                    onPeak: cost_reo_on,
-                    offPeak: cost_reo_off,
-                    fcr: total_fcr,
-                    tax: tou_reo_total - (tou_reo_fixed + tou_reo_energy + total_fcr)
-                }
-            },
-            tou_oa: {
-                total: tou_oa_total,
-                breakdown: {
-                    fixed: tou_oa_fixed,
-                    onPeak: cost_oa_on,
-                    offPeak: cost_oa_off,
-                    superOffPeak: cost_oa_super,
-                    fcr: total_fcr,
-                    tax: tou_oa_total - (tou_oa_fixed + tou_oa_energy + total_fcr)
-                }
-            },
-            tou_rd: {
-                total: tou_rd_total,
-                breakdown: {
-                    fixed: tou_rd_fixed,
-                    onPeak: cost_rd_on,
-                    offPeak: cost_rd_off,
-                    demand: total_demand_charge,
-                    fcr: total_fcr,
-                    tax: tou_rd_total - (tou_rd_fixed + tou_rd_energy + total_demand_charge + total_fcr)
-                }
-            },
-            r30: {
-                total: r30_total,
-                breakdown: {
-                    fixed: 0.4603 * billingDays,
-                    summerTier1: cost_r30_summer_tier1,
-                    summerTier2: cost_r30_summer_tier2,
-                    summerTier3: cost_r30_summer_tier3,
-                    winter: cost_r30_winter,
-                    fcr: total_fcr,
-                    tax: r30_total - (r30_base_total + total_fcr)
-                }
-            },
-            stats: {
-                start: records[0].dt,
-                end: records[records.length - 1].dt,
-                duration: durationDays,
-                totalUsage: records.reduce((sum, r) => sum + r.kwh, 0),
-                note: note
-            }
+        const first = records[0];
+        const firstHour = new Date(first.dt.getFullYear(), first.dt.getMonth(), first.dt.getDate(), first.dt.getHours());
+        const startMs = wallClockInstant(firstHour, GEORGIA_TIME_ZONE, Boolean(first.repeated)).instant;
+        // One calendar year of hours: 8760, or 8784 when it contains Feb 29
+        const hours = Math.round((wallClockInstant(addYears(firstHour, 1), GEORGIA_TIME_ZONE).instant - startMs) / HOUR_MS);
+        const steps = Array.from({ length: hours }, (_, i) => ({ dt: new Date(startMs + i * HOUR_MS), zoned: true }));
+        const monthTotals = {};
+        const curve = [];
+
+        toGeorgiaTime(steps, GEORGIA_TIME_ZONE).series.forEach(({ dt, repeated }, i) => {
+            const monthKey = `${dt.getFullYear()}-${dt.getMonth()}`;
+            const before = monthTotals[monthKey] || 0;
+            const { period, price } = hourlyPrice(planId, dt, before);
+            monthTotals[monthKey] = before + (usageByHour.get(hourKey(dt, repeated)) || 0);
+            curve.push({ start: toLocalIso(dt, steps[i].dt.getTime()), price, period });
         });
+        return curve;
     }
 
-    function isOnPeak(dt) {
-        // Mon(1)..Fri(5) in JS? No, Sun=0, Mon=1...Sat=6.
-        // Rust: Mon=0..Fri=4.
-        // So JS: day >= 1 && day <= 5.
-        const month = dt.getMonth() + 1; // 1-12
-        const hour = dt.getHours();
-        const day = dt.getDay(); // 0=Sun, 1=Mon...
-
-        const isWeekday = day >= 1 && day <= 5;
-        const isSummer = month >= 6 && month <= 9;
-        const isPeakHour = hour >= 14 && hour < 19; // 14:00 - 18:59
+    // A Georgia clock time (local fields of dt) as ISO 8601 with Georgia's offset at instant (ms)
+    function toLocalIso(dt, instant) {
+        const pad = n => String(n).padStart(2, '0');
+        const offset = Math.round((Date.UTC(dt.getFullYear(), dt.getMonth(), dt.getDate(), dt.getHours(), dt.getMinutes()) - instant) / 60000);
+        const sign = offset >= 0 ? '+' : '-';
+        const abs = Math.abs(offset);
+        return `${dt.getFullYear()}-${pad(dt.getMonth() + 1)}-${pad(dt.getDate())}T${pad(dt.getHours())}:${pad(dt.getMinutes())}:00` +
+            `${sign}${pad(Math.floor(abs / 60))}:${pad(abs % 60)}`;
+    }
 
-        return isWeekday && isSummer && isPeakHour;
+    function downloadFile(filename, content, mimeType) {
+        const blob = new Blob([content], { type: mimeType });
+        const url = URL.createObjectURL(blob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = filename;
+        document.body.appendChild(link);
+        link.click();
+        link.remove();
+        URL.revokeObjectURL(url);
     }
 
-    function getTouOaPeriod(dt) {
-        if (isOnPeak(dt)) return 'on_peak';
-        const hour = dt.getHours();
-        if (hour >= 23 || hour < 7) return 'super_off_peak';
-        return 'off_peak';
+    function exportPriceCurve(format) {
+        if (lastRecords.length === 0) return;
+        const planId = document.getElementById('export-plan').value;
+        const curve = buildPriceCurve(planId, lastRecords);
+
+        if (format === 'csv') {
+            const lines = ['timestamp,price_usd_per_kwh,period'];
+            curve.forEach(h => lines.push(`${h.start},${h.price.toFixed(6)},${h.period}`));
+            downloadFile(`hourly_prices_${planId}.csv`, lines.join('\n') + '\n', 'text/csv');
+        } else {
+            const payload = {
+                plan: planId,
+                currency: 'USD',
+                unit: 'kWh',
+                includes: ['energy', 'fuel_cost_recovery', 'taxes_fees'],
+                excludes: ['basic_service_charge', 'demand_charge'],
+                rates_last_verified: RATE_PLANS[planId].lastVerified,
+                hours: curve.length, // 8784 when the year holds February 29
+                prices: curve.map(h => ({ start: h.start, price: Number(h.price.toFixed(6)), period: h.period }))
+            };
+            downloadFile(`hourly_prices_${planId}.json`, JSON.stringify(payload, null, 2), 'application/json');
+        }
     }
 
     function displayResults(results) {
         resultsSection.classList.remove('hidden');
 
-        const plans = [
-            { id: 'tou-reo', name: 'TOU-REO', cost: results.tou_reo.total, breakdown: results.tou_reo.breakdown },
-            { id: 'tou-oa', name: 'TOU-OA', cost: results.tou_oa.total, breakdown: results.tou_oa.breakdown },
-            { id: 'tou-rd', name: 'TOU-RD', cost: results.tou_rd.total, breakdown: results.tou_rd.breakdown },
-            { id: 'r30', name: 'R-30', cost: results.r30.total, breakdown: results.r30.breakdown }
-        ];
+        const plans = PLAN_IDS.map(id => ({
+            id,
+            name: RATE_PLANS[id].name,
+            cost: results.plans[id].total,
+            breakdown: results.plans[id].breakdown
+        }));
+
+        renderStaleWarning();
+        renderPlanWarnings(results.suppressed);
 
-        // Sort by cost
+        // Sort by cost; suppressed plans are left out of the ranking, and only plans
+        // whose required term pays off can be recommended
         plans.sort((a, b) => a.cost - b.cost);
-        const best = plans[0];
+        const selected = getSelectedPlans();
+        const pool = plans.filter(p => selected.includes(p.id));
+        const unsuppressed = pool.filter(p => !results.suppressed[p.id]);
+        const compared = unsuppressed.length > 0 ? unsuppressed : pool;
+        // With R-30 left out of the selection, every remaining plan can fail its term check
+        const best = compared.find(p => results.commitments[p.id].eligible) || compared[0];
+        const cheapest = compared[0];
+        renderComposition(pool);
+        renderWinnerTimeline(results.timeline, compared.map(p => p.id));
+
+        const termNote = document.getElementById('term-note');
+        if (cheapest.id !== best.id) {
+            termNote.textContent = `${cheapest.name} is $${(best.cost - cheapest.cost).toFixed(2)} cheaper over this data but is not recommended: ` +
+                `${results.commitments[cheapest.id].reason}.`;
+            termNote.classList.remove('hidden');
+        } else if (!results.commitments[best.id].eligible) {
+            termNote.textContent = `None of the selected plans is a safe switch from R-30. ${best.name} is the cheapest, but ` +
+                `${results.commitments[best.id].reason}.`;
+            termNote.classList.remove('hidden');
+        } else if (results.commitments[best.id].unverified) {
+            termNote.textContent = `${best.name} beats R-30 over this data, but ${results.commitments[best.id].reason}. ` +
+                'Upload a full year to check every stretch of the term.';
+            termNote.classList.remove('hidden');
+        } else {
+            termNote.classList.add('hidden');
+        }
 
         // Find R-30 cost for comparison
         const r30Plan = plans.find(p => p.id === 'r30');
         const r30Cost = r30Plan ? r30Plan.cost : 0;
 
         // Update UI
-        document.getElementById('best-plan-name').textContent = best.name;
+        const { summer } = results;
+        document.getElementById('best-plan-name').textContent = summer.enough ? best.name : 'No recommendation';
 
         const savings = r30Cost - best.cost;
         const savingsElement = document.getElementById('best-plan-savings');
 
-        if (best.id === 'r30') {
+        if (!summer.enough) {
+            const season = RATE_PLANS['r30'].summer;
+            savingsElement.textContent = `Only ${summer.days} day(s) of summer (${season.from} to ${season.to}) usage, and at least ${summer.minDays} are needed to compare plans. ` +
+                `${best.name} costs least over this data, but summer on-peak prices make most of the difference between plans. ` +
+                'Upload a file that includes a summer, or lower Minimum summer days under Analysis Options.';
+            savingsElement.style.color = "var(--text-primary)";
+        } else if (best.id === 'r30') {
             savingsElement.textContent = "The Standard Residential (R-30) plan is your best option.";
             savingsElement.style.color = "var(--text-primary)";
         } else if (savings > 0) {
@@ -429,41 +2327,52 @@ document.addEventListener('DOMContentLoaded', () => {
             savingsElement.style.color = "var(--success-color)";
         }
 
+        // Against R-30, or the runner-up when R-30 wins or isn't selected, as in the timeline
+        const versus = best.id !== 'r30' && selected.includes('r30') ? 'r30' : (compared.find(p => p.id !== best.id) || {}).id;
+        renderDrivers(summer.enough ? costDrivers({ monthlyUsage: lastMonthlyUsage, totals: results.plans }, best.id, versus) : [], versus);
+
+        renderActualCost(results.actual, best);
+        renderSwitchSummary(best, plans, results);
+
         plans.forEach(p => {
             const card = document.getElementById(`card-${p.id}`);
             document.getElementById(`price-${p.id}`).textContent = `$${p.cost.toFixed(2)}`;
 
             // Highlight best
-            if (p.id === best.id) {
+            if (p.id === best.id && summer.enough) {
                 card.style.borderColor = 'var(--success-color)';
                 card.style.backgroundColor = 'rgba(34, 197, 94, 0.05)';
             } else {
                 card.style.borderColor = 'var(--border-color)';
                 card.style.backgroundColor = 'var(--card-bg)';
             }
+            card.classList.toggle('plan-suppressed', Boolean(results.suppressed[p.id]));
+            card.classList.toggle('hidden', !selected.includes(p.id));
 
             // Add Breakdown
             let breakdownHtml = `<div class="cost-breakdown">`;
 
             // Energy / Demand Section (Fixed Height for Alignment)
             breakdownHtml += `<div class="energy-section">`;
+            const rates = ENERGY_RATES[p.id];
 
             if (p.id === 'tou-reo') {
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (29.8¢/kWh)">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="All other times (7.6¢/kWh)">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (${formatCents(rates.on_peak)})">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="All other times (${formatCents(rates.off_peak)})">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
             } else if (p.id === 'tou-oa') {
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (29.8¢/kWh)">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Oct-May & Weekends (10.2¢/kWh)">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="11PM - 7AM Daily (2.2¢/kWh)">Super Off-Peak:</span><span>$${p.breakdown.superOffPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (${formatCents(rates.on_peak)})">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Oct-May & Weekends (${formatCents(rates.off_peak)})">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="11PM - 7AM Daily (${formatCents(rates.super_off_peak)})">Super Off-Peak:</span><span>$${p.breakdown.superOffPeak.toFixed(2)}</span></div>`;
             } else if (p.id === 'tou-rd') {
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (14.3¢/kWh)">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="All other times (1.5¢/kWh)">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
-                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Highest 60-min usage ($12.21/kW)">Demand:</span><span>$${p.breakdown.demand.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Jun-Sep, M-F, 2-7PM (${formatCents(rates.on_peak)})">On-Peak:</span><span>$${p.breakdown.onPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="All other times (${formatCents(rates.off_peak)})">Off-Peak:</span><span>$${p.breakdown.offPeak.toFixed(2)}</span></div>`;
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Highest ${SERVICE_RATES['tou-rd'].demandWindowMinutes}-min average kW ($${SERVICE_RATES['tou-rd'].demandPerKw.toFixed(2)}/kW)">Demand:</span><span>$${p.breakdown.demand.toFixed(2)}</span></div>`;
             } else if (p.id === 'r30') {
-                if (p.breakdown.summerTier1 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Summer <650kWh (8.6¢/kWh)">Summer Tier 1:</span><span>$${p.breakdown.summerTier1.toFixed(2)}</span></div>`;
-                if (p.breakdown.summerTier2 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Summer 650-1000kWh (14.3¢/kWh)">Summer Tier 2:</span><span>$${p.breakdown.summerTier2.toFixed(2)}</span></div>`;
-                if (p.breakdown.summerTier3 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Summer >1000kWh (14.8¢/kWh)">Summer Tier 3:</span><span>$${p.breakdown.summerTier3.toFixed(2)}</span></div>`;
-                if (p.breakdown.winter > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Oct-May All Usage (8.1¢/kWh)">Winter:</span><span>$${p.breakdown.winter.toFixed(2)}</span></div>`;
+                if (p.breakdown.summerTier1 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="${describeSummerBlock(0)}">Summer Tier 1:</span><span>$${p.breakdown.summerTier1.toFixed(2)}</span></div>`;
+                if (p.breakdown.summerTier2 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="${describeSummerBlock(1)}">Summer Tier 2:</span><span>$${p.breakdown.summerTier2.toFixed(2)}</span></div>`;
+                if (p.breakdown.summerTier3 > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="${describeSummerBlock(2)}">Summer Tier 3:</span><span>$${p.breakdown.summerTier3.toFixed(2)}</span></div>`;
+                if (p.breakdown.winter > 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Oct-May All Usage (${formatCents(rates.winter.rates[0])})">Winter:</span><span>$${p.breakdown.winter.toFixed(2)}</span></div>`;
+                if (p.breakdown.baseLoadCredit < 0) breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Credit on the first block of usage">Base-Load Credit:</span><span>-$${(-p.breakdown.baseLoadCredit).toFixed(2)}</span></div>`;
             }
 
             breakdownHtml += `</div>`; // End energy-section
@@ -472,11 +2381,20 @@ document.addEventListener('DOMContentLoaded', () => {
             breakdownHtml += `<div class="breakdown-separator"></div>`;
 
             // Riders & Fixed
-            breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Pass-through cost for fuel (Coal, Gas, Nuclear) ~4.3-4.6¢/kWh">Fuel Recovery:</span><span>$${p.breakdown.fcr.toFixed(2)}</span></div>`;
-            breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Basic Service Charge ($0.46/day)">Fixed:</span><span>$${p.breakdown.fixed.toFixed(2)}</span></div>`;
+            breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Pass-through cost for fuel (Coal, Gas, Nuclear), ${formatCents(FUEL_RATES.winter)} Oct-May and ${formatCents(FUEL_RATES.summer)} Jun-Sep">Fuel Recovery:</span><span>$${p.breakdown.fcr.toFixed(2)}</span></div>`;
+            breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Basic Service Charge ($${SERVICE_RATES[p.id].basicPerDay.toFixed(2)}/day)">Fixed:</span><span>$${p.breakdown.fixed.toFixed(2)}</span></div>`;
+            if (p.breakdown.kwhRiders) {
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="Per-kWh riders and refunds from Bill adjustments">Rider Adjustments:</span><span>${formatSigned(p.breakdown.kwhRiders)}</span></div>`;
+            }
 
             // Taxes
-            breakdownHtml += `<div class="breakdown-row tax-row"><span>Taxes & Fees (12%):</span><span>$${p.breakdown.tax.toFixed(2)}</span></div>`;
+            breakdownHtml += `<div class="breakdown-separator"></div>`;
+            FEE_LINES.forEach(f => {
+                breakdownHtml += `<div class="breakdown-row tax-row"><span class="tooltip-label" title="${f.title}">${f.label} (${(f.rate * 100).toFixed(0)}%):</span><span>$${p.breakdown[f.key].toFixed(2)}</span></div>`;
+            });
+            if (p.breakdown.oneTime) {
+                breakdownHtml += `<div class="breakdown-row"><span class="tooltip-label" title="One-time bill credits and charges, after taxes">Bill Credits:</span><span>${formatSigned(p.breakdown.oneTime)}</span></div>`;
+            }
             breakdownHtml += `</div>`;
 
             // Check if breakdown already exists to avoid duplicates
@@ -488,10 +2406,30 @@ document.addEventListener('DOMContentLoaded', () => {
             }
         });
 
+        renderCoverage(results.stats);
+        renderCompliance(results.compliance);
+        renderSensitivity(results.sensitivity);
+        renderStress(results.stress);
+        renderStability(results.stability);
+        renderSignificantDates(results.events, selected);
+        renderMeterCheck(results.meterCheck);
+        renderFlatBill(results.flatBill, selected);
+        renderRateChanges(results.rateChanges, selected);
+        renderHeatingEquivalent(results.heating);
+        renderHomeMetrics(results.home);
+        populateMonthPicker();
+
         // Stats
         document.getElementById('data-range').textContent = `${results.stats.start.toLocaleDateString()} - ${results.stats.end.toLocaleDateString()}`;
         document.getElementById('data-duration').textContent = results.stats.duration.toFixed(0);
         document.getElementById('total-usage').textContent = results.stats.totalUsage.toFixed(2);
+        document.getElementById('clock-check').textContent = describeClock(results.stats.clock);
+        document.getElementById('data-source').textContent = lastSource
+            ? [lastSource.label, ...lastSource.notes].join('; ')
+            : '--';
+        renderCircuits(lastSource ? lastSource.circuits : [], results.stats.totalUsage);
+        document.getElementById('skipped-rows').textContent = summarizeSkipped(results.stats.rowStats);
+        renderSkipSamples(document.getElementById('skipped-samples'), results.stats.rowStats);
 
         // Add note if present
         let noteEl = document.getElementById('data-note');
@@ -516,4 +2454,740 @@ document.addEventListener('DOMContentLoaded', () => {
         }
         ratesNote.innerHTML = 'Rates effective Jan 2025.<br>Includes estimated Fuel Cost Recovery (~4.3-4.6¢/kWh) and Taxes/Fees (~12%) to match actual bills.';
     }
+
+    // The costDrivers() list under the recommendation, each with what it saves or adds
+    function renderDrivers(drivers, versus) {
+        const el = document.getElementById('plan-drivers');
+        el.innerHTML = drivers.map(d => {
+            const text = d.text[0].toUpperCase() + d.text.slice(1);
+            return `<li>${text}: ${d.amount < 0 ? 'saves' : 'adds'} $${Math.abs(d.amount).toFixed(2)} against ${RATE_PLANS[versus].name}.</li>`;
+        }).join('');
+        el.classList.toggle('hidden', drivers.length === 0);
+    }
+
+    // Requested range vs. actual coverage, shown above the results
+    function renderCoverage(stats) {
+        const cov = stats.coverage;
+        const el = document.getElementById('coverage-report');
+        const lines = [];
+
+        if (cov.requestedStart || cov.requestedEnd) {
+            lines.push(`Requested: ${formatRange(cov.requestedStart, cov.requestedEnd)}`);
+        }
+        lines.push(`Data available: ${cov.dataStart.toLocaleDateString()} - ${cov.dataEnd.toLocaleDateString()}`);
+        lines.push(`Analyzed: ${stats.start.toLocaleDateString()} - ${stats.end.toLocaleDateString()} (${stats.duration.toFixed(0)} days)`);
+        if (cov.missingMonths.length > 0) {
+            lines.push(`Missing months: ${cov.missingMonths.join(', ')}`);
+        }
+        cov.strayDates.forEach(note => lines.push(`${note}.`));
+        cov.meterChanges.forEach(note => lines.push(`${escapeHtml(note)}.`));
+        if (cov.fileOverlap) lines.push(`${cov.fileOverlap}.`);
+        if (cov.gaps.gaps.length > 0) {
+            const longest = cov.gaps.gaps.reduce((a, b) => (b.missing > a.missing ? b : a));
+            lines.push(`${describeMissing(cov.gaps)}, the longest ${formatHours(longest.missing * cov.gaps.minutes / 60)} ` +
+                `from ${longest.from.toLocaleString()}. ` + (cov.gaps.filled
+                    ? `${cov.gaps.filled.readings} reading(s) were filled ${GAP_FILL_METHODS[cov.gaps.filled.method]}, so these months' bills rest partly on estimates:`
+                    : 'Months with gaps bill low by the usage that went unrecorded:'));
+            Object.keys(cov.gaps.months).sort().forEach(key => {
+                const m = cov.gaps.months[key];
+                lines.push(`${key}: ${formatHours(m.missing * cov.gaps.minutes / 60)} missing in ${m.gaps} gap${m.gaps === 1 ? '' : 's'}`);
+            });
+        }
+
+        const short = (cov.requestedStart && daysBetween(cov.requestedStart, stats.start) > 1) ||
+            (cov.requestedEnd && daysBetween(stats.end, cov.requestedEnd) > 2) ||
+            cov.missingMonths.length > 0 ||
+            cov.strayDates.length > 0 ||
+            cov.gaps.gaps.length > 0 ||
+            Boolean(cov.fileOverlap);
+
+        el.classList.toggle('coverage-warning', Boolean(short));
+        el.innerHTML = lines.map(l => `<div>${l}</div>`).join('');
+    }
+
+    // Readings repeated across combined files, or null. Adapted sources were merged before
+    // processData (lastSource.overlap); GA Power rows repeat until processData drops them.
+    function describeFileOverlap(rowStats) {
+        if (!lastSource || !(lastSource.files > 1)) return null;
+        const overlap = lastSource.overlap || (rowStats.repeated && {
+            count: rowStats.skipped.duplicate,
+            conflicts: rowStats.conflicts.length,
+            ...rowStats.repeated
+        });
+        if (!overlap) return null;
+        return `Files overlap: ${overlap.count} reading(s) from ${overlap.from.toLocaleString()} to ${overlap.to.toLocaleString()} ` +
+            `appear in more than one file and were counted once, ${DEDUPE_POLICIES[rowStats.dedupePolicy].describe}` +
+            (overlap.conflicts > 0 ? `; ${overlap.conflicts} of them differ between files` : '');
+    }
+
+    // "12 repeated reading(s) resolved: the larger reading was kept", or null with none. Adapted
+    // sources were settled while reading (lastSource.duplicates).
+    function describeDuplicates(rowStats) {
+        const count = (rowStats.skipped.duplicate || 0) + ((lastSource && lastSource.duplicates) || 0);
+        if (count === 0) return null;
+        return `${count} repeated reading(s) resolved: ${DEDUPE_POLICIES[rowStats.dedupePolicy].describe}`;
+    }
+
+    // Clock check line: the time offset applied and how far off the timestamps look
+    function describeClock(clock) {
+        const applied = clock.offsetMinutes !== 0 ? `shifted ${clock.offsetMinutes > 0 ? '+' : ''}${clock.offsetMinutes} min; ` : '';
+        if (!clock.check.detected) {
+            return `${applied}${clock.check.suggested !== undefined ? 'looks right' : 'not checked'} (${clock.check.reason})`;
+        }
+        const total = clock.offsetMinutes + clock.check.suggested;
+        return `${applied}timestamps look ${Math.abs(clock.check.suggested)} min ${clock.check.suggested > 0 ? 'early' : 'late'} ` +
+            `(${clock.check.reason}); try a time offset of ${total} min`;
+    }
+
+    // Branch circuit totals from monitor exports, as a share of the billed usage
+    function renderCircuits(circuits, totalUsage) {
+        const container = document.getElementById('source-circuits');
+        if (!circuits || circuits.length === 0) {
+            container.innerHTML = '';
+            return;
+        }
+        container.innerHTML = `<p>Circuits (whole export):</p><ul>` +
+            circuits.map(c => `<li>${escapeHtml(String(c.name))}: ${c.kwh.toFixed(0)} kWh` +
+                (totalUsage > 0 ? ` (${(c.kwh / totalUsage * 100).toFixed(1)}% of analyzed usage)` : '') + `</li>`).join('') +
+            `</ul>`;
+    }
+
+    // Sample offending lines per skip reason (line numbers match the spreadsheet rows)
+    function renderSkipSamples(container, stats) {
+        container.replaceChildren();
+        Object.keys(SKIP_REASONS).forEach(reason => {
+            const samples = stats.samples[reason] || [];
+            if (samples.length === 0) return;
+
+            const heading = document.createElement('p');
+            heading.textContent = `${SKIP_REASONS[reason]} (${stats.skipped[reason]} total):`;
+            container.appendChild(heading);
+
+            const list = document.createElement('ul');
+            samples.forEach(sample => {
+                const item = document.createElement('li');
+                item.textContent = `Row ${sample.line}: ${sample.text}`;
+                list.appendChild(item);
+            });
+            container.appendChild(list);
+        });
+    }
+
+    // Plans whose rates have not been checked against the tariff sheets within the threshold
+    function findStalePlans(now = new Date()) {
+        const months = parseInt(document.getElementById('stale-months').value, 10);
+        if (!(months > 0)) return [];
+        return PLAN_IDS.filter(id => {
+            const [y, m, d] = RATE_PLANS[id].lastVerified.split('-').map(Number);
+            const expires = new Date(y, m - 1 + months, d);
+            return now >= expires;
+        });
+    }
+
+    function renderStaleWarning() {
+        const el = document.getElementById('stale-warning');
+        const stale = findStalePlans();
+        if (stale.length === 0) {
+            el.classList.add('hidden');
+            return;
+        }
+        el.innerHTML = `<p><strong>Rates may be out of date:</strong> ` +
+            stale.map(id => `${RATE_PLANS[id].name} (verified ${RATE_PLANS[id].lastVerified})`).join(', ') +
+            ` ${stale.length === 1 ? 'was' : 'were'} last checked more than ${document.getElementById('stale-months').value} months ago. ` +
+            `Compare with the current tariff sheets at georgiapower.com before switching.</p>`;
+        el.classList.remove('hidden');
+    }
+
+    function renderPlanWarnings(suppressed) {
+        const el = document.getElementById('plan-warnings');
+        const ids = Object.keys(suppressed);
+        if (ids.length === 0) {
+            el.classList.add('hidden');
+            return;
+        }
+        el.innerHTML = ids
+            .map(id => `<p><strong>${RATE_PLANS[id].name} not compared:</strong> ${suppressed[id]}.</p>`)
+            .join('');
+        el.classList.remove('hidden');
+    }
+
+    // --- Monthly Detail ---
+
+    // Breakdown keys grouped into the categories of the composition report
+    const COMPOSITION_CATEGORIES = [
+        { label: 'Fixed', group: 'fixed', keys: ['fixed'] },
+        { label: 'Energy: on-peak', group: 'energy', keys: ['onPeak'] },
+        { label: 'Energy: off-peak', group: 'energy', keys: ['offPeak'] },
+        { label: 'Energy: super off-peak', group: 'energy', keys: ['superOffPeak'] },
+        { label: 'Energy: summer blocks', group: 'energy', keys: ['summerTier1', 'summerTier2', 'summerTier3'] },
+        { label: 'Energy: winter', group: 'energy', keys: ['winter', 'baseLoadCredit'] },
+        { label: 'Demand', group: 'demand', keys: ['demand'] },
+        { label: 'Fuel recovery', group: 'fuel', keys: ['fcr'] },
+        { label: 'Rider adjustments', group: 'riders', keys: ['kwhRiders'] },
+        { label: 'Riders, fees & taxes', group: 'taxes', keys: FEE_LINES.map(f => f.key) },
+        { label: 'Bill credits', group: 'credits', keys: ['oneTime'] }
+    ];
+
+    // Each plan's total split into categories, in dollars and as a share of the total,
+    // plus a sentence on how the cheapest plan's mix differs from R-30's
+    function renderComposition(plans) {
+        const amountOf = (p, keys) => keys.reduce((sum, k) => sum + (p.breakdown[k] || 0), 0);
+        const rows = COMPOSITION_CATEGORIES.filter(c => plans.some(p => Math.abs(amountOf(p, c.keys)) >= 0.005));
+
+        let html = `<table class="month-table"><thead><tr><th>Category</th>${plans.map(p => `<th>${p.name}</th>`).join('')}</tr></thead><tbody>`;
+        rows.forEach(c => {
+            html += `<tr><td>${c.label}</td>` + plans.map(p => {
+                const amount = amountOf(p, c.keys);
+                return `<td>${formatSigned(amount)} (${(amount / p.cost * 100).toFixed(0)}%)</td>`;
+            }).join('') + `</tr>`;
+        });
+        html += `<tr><td><strong>Total</strong></td>${plans.map(p => `<td><strong>$${p.cost.toFixed(2)}</strong></td>`).join('')}</tr>`;
+        html += `</tbody></table>`;
+
+        // Compare group shares (energy, demand, ...) of the cheapest plan against R-30
+        const r30 = plans.find(p => p.id === 'r30');
+        const cheapest = plans[0];
+        if (r30 && cheapest && cheapest.id !== 'r30') {
+            const groupShare = p => {
+                const shares = {};
+                COMPOSITION_CATEGORIES.forEach(c => { shares[c.group] = (shares[c.group] || 0) + amountOf(p, c.keys) / p.cost * 100; });
+                return shares;
+            };
+            const a = groupShare(cheapest);
+            const b = groupShare(r30);
+            const diffs = Object.keys(a).map(g => ({ group: g, diff: a[g] - b[g] })).sort((x, y) => x.diff - y.diff);
+            const less = diffs[0];
+            const more = diffs[diffs.length - 1];
+            if (less.diff < -1 && more.diff > 1) {
+                html += `<p>${cheapest.name} is ${Math.round(a[less.group])}% ${less.group} (R-30: ${Math.round(b[less.group])}%) and ` +
+                    `${Math.round(a[more.group])}% ${more.group} (R-30: ${Math.round(b[more.group])}%), ` +
+                    `so it trades ${less.group} cost for ${more.group} cost.</p>`;
+            }
+        }
+        document.getElementById('composition-report').innerHTML = html;
+    }
+
+    // Per-month confidence from data quality. Coverage is the share of the month's analyzed span
+    // (first to last reading for the end months) that has readings; estimated is the share of
+    // kWh the file flags as estimated or whose dates were repaired; conflicts are duplicate
+    // rows whose kWh disagreed. A month takes the first label whose limits it meets. With the
+    // findGaps() result, coverage comes from the readings it found missing instead, so rows of
+    // zero kWh, which were read but not billed, count as covered.
+    const CONFIDENCE_LEVELS = [
+        { label: 'high', minCoverage: 98, maxEstimated: 2, maxConflicts: 0 },
+        { label: 'medium', minCoverage: 90, maxEstimated: 10, maxConflicts: Infinity },
+        { label: 'low', minCoverage: 0, maxEstimated: 100, maxConflicts: Infinity }
+    ];
+
+    function rateMonthConfidence(records, conflicts, gaps = null) {
+        const monthKey = dt => `${dt.getFullYear()}-${String(dt.getMonth() + 1).padStart(2, '0')}`;
+        const minutes = readingMinutes(records);
+        const months = {};
+        records.forEach((r, i) => {
+            const key = monthKey(r.dt);
+            const m = months[key] || (months[key] = { covered: 0, kwh: 0, estimatedKwh: 0, conflicts: 0 });
+            m.covered += minutes[i];
+            m.kwh += r.kwh;
+            if (r.estimated) m.estimatedKwh += r.kwh;
+        });
+        conflicts.forEach(dt => {
+            if (months[monthKey(dt)]) months[monthKey(dt)].conflicts++;
+        });
+
+        const spanStart = records[0].dt;
+        const spanEnd = new Date(records[records.length - 1].dt.getTime() + minutes[minutes.length - 1] * 60000);
+        const result = {};
+        Object.entries(months).forEach(([key, m]) => {
+            const [y, mo] = key.split('-').map(Number);
+            const from = Math.max(new Date(y, mo - 1, 1), spanStart);
+            const to = Math.min(new Date(y, mo, 1), spanEnd);
+            const missing = gaps && gaps.months[key] ? gaps.months[key].missing * gaps.minutes : 0;
+            const coverage = gaps
+                ? Math.max(0, 100 - missing / ((to - from) / 60000) * 100)
+                : Math.min(100, m.covered / ((to - from) / 60000) * 100);
+            const estimated = m.kwh > 0 ? m.estimatedKwh / m.kwh * 100 : 0;
+            const level = CONFIDENCE_LEVELS.find(l => coverage >= l.minCoverage && estimated <= l.maxEstimated && m.conflicts <= l.maxConflicts);
+            const reasons = [];
+            if (missing > 0) reasons.push(`${formatHours(missing / 60)} missing (${coverage.toFixed(0)}% of hours read)`);
+            else if (coverage < 99.5) reasons.push(`${coverage.toFixed(0)}% of hours read`);
+            if (estimated >= 0.5) reasons.push(`${estimated.toFixed(0)}% of kWh estimated`);
+            if (m.conflicts > 0) reasons.push(`${m.conflicts} conflicting duplicate(s)`);
+            result[key] = { label: level.label, coverage, estimated, conflicts: m.conflicts, missing, reasons };
+        });
+        return result;
+    }
+
+    function formatHours(hours) {
+        const value = Number.isInteger(hours) ? hours : Number(hours.toFixed(1));
+        return `${value} hour${value === 1 ? '' : 's'}`;
+    }
+
+    // "26 hours of readings missing in 2 gaps", for a findGaps() result with its interval minutes
+    function describeMissing({ gaps, minutes }) {
+        const missing = gaps.reduce((n, g) => n + g.missing, 0);
+        return `${formatHours(missing * minutes / 60)} of readings missing in ${gaps.length} gap${gaps.length === 1 ? '' : 's'}`;
+    }
+
+    function describeConfidence(confidence) {
+        if (!confidence) return '';
+        return confidence.reasons.length > 0 ? `${confidence.label} confidence: ${confidence.reasons.join(', ')}` : `${confidence.label} confidence`;
+    }
+
+    // One line per month: the cheapest plan, bars for its bill and the comparison plan's
+    // (R-30, or the runner-up when R-30 wins or isn't selected), and the difference
+    const BAR_LEVELS = '▁▂▃▄▅▆▇█';
+
+    function renderWinnerTimeline(timeline, ids) {
+        const container = document.getElementById('timeline-report');
+        const top = Math.max(...timeline.flatMap(month => ids.map(id => month.bills[id].total))) || 1;
+        const bar = amount => BAR_LEVELS[Math.max(0, Math.min(BAR_LEVELS.length - 1, Math.round(amount / top * (BAR_LEVELS.length - 1))))];
+        const width = Math.max(...ids.map(id => RATE_PLANS[id].name.length));
+
+        const lines = timeline.map(({ key, bills, days, partial, confidence }) => {
+            const ranked = [...ids].sort((a, b) => bills[a].total - bills[b].total);
+            const winner = ranked[0];
+            const versus = winner !== 'r30' && ids.includes('r30') ? 'r30' : ranked[1];
+            let line = `${key}: ${RATE_PLANS[winner].name.padEnd(width)} ${bar(bills[winner].total)}`;
+            if (versus) {
+                const margin = bills[versus].total - bills[winner].total;
+                line += `${bar(bills[versus].total)} saves $${margin.toFixed(0)} vs ${RATE_PLANS[versus].name}`;
+            }
+            if (partial) line += ` (partial month, ${days} days)`;
+            return confidence && (confidence.label !== 'high' || confidence.missing > 0) ? `${line} [${describeConfidence(confidence)}]` : line;
+        });
+        container.innerHTML = `<pre class="month-timeline">${lines.join('\n')}</pre>`;
+    }
+
+    function renderStress(stress) {
+        const section = document.getElementById('stress-section');
+        if (!stress) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const container = document.getElementById('stress-report');
+        if (stress.days === 0) {
+            container.innerHTML = '<p>No summer days in the analyzed range to stress.</p>';
+            return;
+        }
+        let html = `<p>The ${stress.days} highest-usage summer day(s) scaled by ${stress.factor}x.</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>As used</th><th>Heat wave</th><th>Change</th><th>Rank</th></tr></thead><tbody>`;
+        Object.entries(stress.plans).forEach(([id, p]) => {
+            const change = p.stressed - p.base;
+            html += `<tr><td>${RATE_PLANS[id].name}</td><td>$${p.base.toFixed(2)}</td><td>$${p.stressed.toFixed(2)}</td>` +
+                `<td>${formatSigned(change)} (${(change / p.base * 100).toFixed(1)}%)</td>` +
+                `<td>${p.rankBefore === p.rankAfter ? p.rankBefore : `${p.rankBefore} &rarr; ${p.rankAfter}`}</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        container.innerHTML = html;
+    }
+
+    function renderStability(stability) {
+        const section = document.getElementById('stability-section');
+        if (!stability) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const share = id => stability.wins[id] / stability.samples * 100;
+        const ranked = Object.keys(stability.wins).sort((a, b) => stability.wins[b] - stability.wins[a]);
+        let html = `<p>${RATE_PLANS[stability.asUsed].name} wins in ${share(stability.asUsed).toFixed(0)}% of ${stability.samples} resampled years. ` +
+            `Each sample swaps every day's usage for a random day from the same month and day type (weekday, or weekend and off-peak holiday), then picks the ` +
+            `plan the same way as the recommendation above.</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>Samples won</th><th>Share</th></tr></thead><tbody>`;
+        ranked.forEach(id => {
+            html += `<tr><td>${RATE_PLANS[id].name}${id === stability.asUsed ? ' (as used)' : ''}</td><td>${stability.wins[id]}</td><td>${share(id).toFixed(0)}%</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        document.getElementById('stability-report').innerHTML = html;
+    }
+
+    function renderSignificantDates(events, selected) {
+        const section = document.getElementById('events-section');
+        if (!events) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const span = s => s.from === s.to ? s.from : `${s.from} to ${s.to}`;
+        let html = `<p>Up to ${EVENT_WINDOW_MONTHS} whole months on each side of a date, leaving out the month it falls in (unless it is the 1st). ` +
+            `Bills are per 30 days; when the two sides fall in different seasons, weather is part of the change.</p>`;
+        events.items.forEach(event => {
+            html += `<h4>${escapeHtml(event.label)} (${event.key})</h4>`;
+            if (!event.before || !event.after) {
+                html += `<p>No whole months of data ${!event.before ? 'before' : 'after'} this date to compare.</p>`;
+                return;
+            }
+            const { before, after } = event;
+            const usageChange = (after.kwhPerDay - before.kwhPerDay) / before.kwhPerDay * 100;
+            html += `<p>${span(before)} vs ${span(after)}: ${before.kwhPerDay.toFixed(1)} &rarr; ${after.kwhPerDay.toFixed(1)} kWh/day ` +
+                `(${usageChange >= 0 ? '+' : ''}${usageChange.toFixed(1)}%).</p>`;
+            html += `<table class="month-table"><thead><tr><th>Plan</th><th>Before</th><th>After</th><th>Change per 30 days</th></tr></thead><tbody>`;
+            selected.forEach(id => {
+                html += `<tr><td>${RATE_PLANS[id].name}</td><td>$${before.per30[id].toFixed(2)}</td><td>$${after.per30[id].toFixed(2)}</td>` +
+                    `<td>${formatSigned(after.per30[id] - before.per30[id])}</td></tr>`;
+            });
+            html += `</tbody></table>`;
+        });
+        if (events.errors.length > 0) {
+            html += `<p>Ignored line(s): ${events.errors.map(escapeHtml).join('; ')}</p>`;
+        }
+        document.getElementById('events-report').innerHTML = html;
+    }
+
+    function renderMeterCheck(meterCheck) {
+        const section = document.getElementById('meter-check-section');
+        if (!meterCheck) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const STATUS = {
+            ok: 'Matches',
+            low: 'Interval data short: readings may be missing',
+            high: 'Interval data over the bill',
+            'no-data': 'Not in the analyzed range'
+        };
+        const flagged = meterCheck.rows.filter(r => r.status === 'low' || r.status === 'high');
+        let html;
+        if (flagged.length > 0) {
+            html = `<p><strong>${flagged.length} month(s) don't match the bill</strong> (${flagged.map(r => r.key).join(', ')}). ` +
+                `Treat the comparison for those months with care, or narrow the date range to skip them.</p>`;
+        } else if (meterCheck.rows.some(r => r.status === 'ok')) {
+            html = `<p>Every checked month matches the billed kWh.</p>`;
+        } else {
+            html = `<p>None of the billed months fall in the analyzed range.</p>`;
+        }
+        html += `<table class="month-table"><thead><tr><th>Month</th><th>Billed kWh</th><th>Interval kWh</th><th>Difference</th><th>Status</th></tr></thead><tbody>`;
+        meterCheck.rows.forEach(r => {
+            html += `<tr><td>${r.key}</td><td>${r.billed.toFixed(0)}</td>` +
+                `<td>${r.interval === null ? '-' : r.interval.toFixed(0)}</td>` +
+                `<td>${r.diffPct === null ? '-' : `${r.diffPct >= 0 ? '+' : ''}${r.diffPct.toFixed(1)}%`}</td>` +
+                `<td>${STATUS[r.status]}</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        if (meterCheck.errors.length > 0) {
+            html += `<p>Ignored line(s): ${meterCheck.errors.map(escapeHtml).join('; ')}</p>`;
+        }
+        document.getElementById('meter-check-report').innerHTML = html;
+    }
+
+    function renderSensitivity(sensitivity) {
+        const items = Object.entries(sensitivity.plans).map(([id, p]) => {
+            const share = sensitivity.totalKwh > 0 ? p.nearKwh / sensitivity.totalKwh * 100 : 0;
+            return `<li><strong>${RATE_PLANS[id].name}</strong>: ${p.nearKwh.toFixed(0)} kWh (${share.toFixed(1)}%) near a boundary; ` +
+                `the bill could move by up to $${p.swing.toFixed(2)}.</li>`;
+        });
+        document.getElementById('sensitivity-report').innerHTML =
+            `<p>Energy within ${sensitivity.windowMin} minutes of a period change. If the meter clock is off or timestamps mark ` +
+            `the end rather than the start of each hour, this is the usage that would be priced in the wrong period.</p>` +
+            `<ul class="compliance-list">${items.join('')}</ul>`;
+    }
+
+    // Plain-text summary of a recommended switch, to paste into the plan-change request or keep
+    // with your records. The current plan is the last one in the plan history, or R-30.
+    // Hidden when the best plan is the current one or its term check fails.
+    function renderSwitchSummary(best, plans, results) {
+        const section = document.getElementById('switch-section');
+        const history = parseEnrollmentHistory(document.getElementById('enrollment-history').value).periods;
+        const currentId = history.length > 0 ? history[history.length - 1].planId : 'r30';
+        const current = plans.find(p => p.id === currentId);
+        if (best.id === currentId || !current || !results.commitments[best.id].eligible || !results.summer.enough) {
+            section.classList.add('hidden');
+            lastSwitchSummary = null;
+            return;
+        }
+        const { stats } = results;
+        const savings = current.cost - best.cost;
+        const oneYear = Math.abs(stats.duration - 365) <= 1;
+        const annualized = oneYear ? savings : savings * 365 / stats.duration;
+        const describe = id => {
+            const plan = RATE_PLANS[id];
+            const marketed = plan.aliases && plan.aliases.length > 0 ? `, sold as ${plan.aliases[0]}` : '';
+            return `${plan.name}, rate schedule ${plan.schedule} (${plan.title}${marketed})`;
+        };
+        const verified = [...new Set([best.id, currentId].map(id => RATE_PLANS[id].lastVerified))].join(' and ');
+        const lines = [
+            'Georgia Power residential rate plan change',
+            '',
+            `Requested plan:   ${describe(best.id)}`,
+            `Current plan:     ${describe(currentId)}`,
+            `Expected savings: $${annualized.toFixed(2)} in the first year` +
+                (!oneYear ? ` (annualized from $${savings.toFixed(2)} over ${stats.duration.toFixed(0)} days)` : ''),
+            `Minimum stay:     ${RATE_PLANS[best.id].minTermMonths > 0 ? `${RATE_PLANS[best.id].minTermMonths} months` : 'none'}`,
+            `Data analyzed:    ${stats.start.toLocaleDateString()} - ${stats.end.toLocaleDateString()} ` +
+                `(${stats.duration.toFixed(0)} days, ${stats.totalUsage.toFixed(0)} kWh)` +
+                (lastSource ? `, from ${[lastSource.label, ...lastSource.notes].join('; ')}` : ''),
+            '',
+            'Assumptions:',
+            `- Rates: ${loadedRates ? `rates file ${loadedRates.name}` : `the published tariff sheets, last checked ${verified}`}`,
+            `- Fuel recovery: ${formatCents(FUEL_RATES.summer)} Jun-Sep, ${formatCents(FUEL_RATES.winter)} Oct-May`,
+            `- Riders, fees and taxes: ${((TAX_RATE - 1) * 100).toFixed(0)}% of the bill, as on actual bills (franchise fee and sales tax vary by location)`,
+            `- Future usage matches the data analyzed. ${stats.note}`,
+            ...(results.commitments[best.id].unverified
+                ? [`- The ${RATE_PLANS[best.id].minTermMonths}-month stay was checked against only ${results.commitments[best.id].months} month(s) of data`]
+                : []),
+            '- An estimate from published rates, not a Georgia Power quote.',
+            '',
+            `Prepared ${new Date().toLocaleDateString()} with the Georgia Power Rate Plan Analyzer.`
+        ];
+        lastSwitchSummary = lines.join('\n');
+        document.getElementById('switch-summary').textContent = lastSwitchSummary;
+        section.classList.remove('hidden');
+    }
+
+    // Actual cost under the recorded plan history next to the recommendation
+    function renderActualCost(actual, best) {
+        const el = document.getElementById('actual-cost');
+        if (!actual) {
+            el.classList.add('hidden');
+            return;
+        }
+        const parts = [];
+        if (actual.segments.length > 0) {
+            // Collapse the month list into runs like "R-30 (2024-01 to 2024-05)"
+            const runs = [];
+            actual.segments.forEach(m => {
+                const last = runs[runs.length - 1];
+                if (last && last.planId === m.planId) last.end = m.key;
+                else runs.push({ planId: m.planId, start: m.key, end: m.key });
+            });
+            const timeline = runs.map(r => `${RATE_PLANS[r.planId].name} (${r.start === r.end ? r.start : `${r.start} to ${r.end}`})`).join(', then ');
+            const diff = actual.total - best.cost;
+            parts.push(`Your actual plan history, ${timeline}, cost $${actual.total.toFixed(2)}` +
+                (Math.abs(diff) < 0.005 ? `, the same as ${best.name}.` : `, $${Math.abs(diff).toFixed(2)} ${diff > 0 ? 'more' : 'less'} than ${best.name}.`));
+            if (actual.splitMonths.length > 0) {
+                parts.push(`Switch month(s) ${actual.splitMonths.join(', ')} are split by timestamp, with the basic service charge prorated by days on each plan.`);
+            }
+        }
+        if (actual.errors.length > 0) {
+            parts.push(`Ignored plan history line(s): ${actual.errors.join('; ')}.`);
+        }
+        el.textContent = parts.join(' ');
+        el.classList.remove('hidden');
+    }
+
+    function renderCompliance(compliance) {
+        const container = document.getElementById('compliance-report');
+        if (compliance.shiftableKwh === 0) {
+            container.innerHTML = `<p>No large shiftable blocks were found (nothing ran ${SHIFTABLE_MIN_KW} kW above the day's typical draw).</p>`;
+            return;
+        }
+        const share = (compliance.shiftableKwh / compliance.totalKwh * 100).toFixed(1);
+        let html = `<p>${compliance.shiftableKwh.toFixed(0)} kWh (${share}% of usage) looks like controllable load: ` +
+            `blocks running at least ${SHIFTABLE_MIN_KW} kW above the day's typical draw.</p><ul class="compliance-list">`;
+        Object.entries(compliance.plans).forEach(([id, p]) => {
+            const period = RATE_PLANS[id].cheapestPeriod.replace(/_/g, '-');
+            html += `<li><strong>${RATE_PLANS[id].name}</strong>: ${(p.score * 100).toFixed(0)}% already lands in ${period}; ` +
+                `${p.headroom.toFixed(0)} kWh could still move.</li>`;
+        });
+        html += `</ul>`;
+        container.innerHTML = html;
+    }
+
+    function renderFlatBill(flatBill, selected) {
+        const section = document.getElementById('flatbill-section');
+        if (!flatBill) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const container = document.getElementById('flatbill-report');
+        if (flatBill.keys.length === 0) {
+            container.innerHTML = `<p>No complete months in the analyzed range to base a fixed payment on.</p>`;
+            return;
+        }
+        const { keys } = flatBill;
+        const span = keys.length === 1 ? keys[0] : `${keys[0]} to ${keys[keys.length - 1]}`;
+        let html = `<p>Fixed payment of <strong>$${flatBill.monthly.toFixed(2)}/month</strong> ($${flatBill.annual.toFixed(2)} a year): ` +
+            `the average R-30 bill over ${span} plus ${flatBill.premiumPct}%.` +
+            (keys.length < FLATBILL_MONTHS ? ` Only ${keys.length} complete month(s) are available, so yearly figures are scaled up from them.` : '') +
+            `</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>Per year</th><th>Lowest month</th><th>Highest month</th><th>FlatBill vs plan</th></tr></thead><tbody>`;
+        html += `<tr><td>FlatBill</td><td>$${flatBill.annual.toFixed(2)}</td><td>$${flatBill.monthly.toFixed(2)}</td><td>$${flatBill.monthly.toFixed(2)}</td><td>-</td></tr>`;
+        selected.forEach(id => {
+            const p = flatBill.plans[id];
+            html += `<tr><td>${RATE_PLANS[id].name}</td><td>$${p.annual.toFixed(2)}</td><td>$${p.low.toFixed(2)}</td><td>$${p.high.toFixed(2)}</td>` +
+                `<td>$${Math.abs(flatBill.annual - p.annual).toFixed(2)} ${flatBill.annual >= p.annual ? 'more' : 'less'} a year</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        container.innerHTML = html;
+    }
+
+    // What the loaded rates file changes against the built-in rates, and what that does to
+    // the bills for the usage being analyzed
+    function renderRateChanges(rateChanges, selected) {
+        const section = document.getElementById('rate-changes-section');
+        if (!rateChanges) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const container = document.getElementById('rate-changes-report');
+        const { changes, before, after } = rateChanges;
+        const pct = value => `${value >= 0 ? '+' : ''}${value.toFixed(1)}%`;
+        const show = value => {
+            if (value === null) return '-';
+            return escapeHtml(typeof value === 'object' ? JSON.stringify(value) : String(value));
+        };
+        let html = `<p>${escapeHtml(rateChanges.name)} against the built-in rates: ` +
+            (changes.length === 0 ? 'no differences.' : `${changes.length} change(s).`) + `</p>`;
+        if (changes.length > 0) {
+            html += `<table class="month-table"><thead><tr><th>Rate</th><th>Built-in</th><th>File</th><th>Change</th></tr></thead><tbody>`;
+            changes.forEach(c => {
+                html += `<tr><td>${escapeHtml(c.what)}</td><td>${show(c.from)}</td><td>${show(c.to)}</td>` +
+                    `<td>${c.changePct === null ? '-' : pct(c.changePct)}</td></tr>`;
+            });
+            html += `</tbody></table>`;
+        }
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>Built-in rates</th><th>File rates</th><th>Difference</th></tr></thead><tbody>`;
+        selected.forEach(id => {
+            const diff = after[id].total - before[id].total;
+            const share = before[id].total ? ` (${pct(diff / before[id].total * 100)})` : '';
+            html += `<tr><td>${RATE_PLANS[id].name}</td><td>$${before[id].total.toFixed(2)}</td><td>$${after[id].total.toFixed(2)}</td>` +
+                `<td>${diff >= 0 ? '+' : '-'}$${Math.abs(diff).toFixed(2)}${share}</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        container.innerHTML = html;
+    }
+
+    function renderHeatingEquivalent(heating) {
+        const section = document.getElementById('heating-section');
+        if (!heating) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const container = document.getElementById('heating-report');
+        if (heating.kwh === 0) {
+            container.innerHTML = `<p>No October-May usage in the analyzed range.</p>`;
+            return;
+        }
+        let html = `<p>Winter (Oct-May) energy cost per unit of heat delivered at COP ${heating.cop}, ` +
+            `over ${heating.kwh.toFixed(0)} kWh. Compare with your gas bill's total cost per therm divided by furnace efficiency.</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>$/kWh</th><th>$/MMBtu</th><th>$/therm</th></tr></thead><tbody>`;
+        Object.entries(heating.plans).forEach(([id, p]) => {
+            html += `<tr><td>${RATE_PLANS[id].name}</td><td>$${p.perKwh.toFixed(4)}</td>` +
+                `<td>$${p.perMMBtu.toFixed(2)}</td><td>$${(p.perMMBtu / 10).toFixed(3)}</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        container.innerHTML = html;
+    }
+
+    function renderHomeMetrics(home) {
+        const section = document.getElementById('home-section');
+        if (!home) {
+            section.classList.add('hidden');
+            return;
+        }
+        section.classList.remove('hidden');
+        const usage = [
+            home.kwhPerSqftYear !== null ? `${home.kwhPerSqftYear.toFixed(2)} kWh per square foot` : null,
+            home.kwhPerPersonYear !== null ? `${home.kwhPerPersonYear.toFixed(0)} kWh per person` : null
+        ].filter(Boolean).join(' and ');
+        const size = [home.sqft > 0 ? `${home.sqft} ft²` : null, home.occupants > 0 ? `${home.occupants} occupant(s)` : null].filter(Boolean).join(', ');
+        let html = `<p>${size}: ${usage} a year` +
+            (Math.abs(home.days - 365) > 1 ? `, annualized from ${home.days.toFixed(0)} days` : '') + `.</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th>` +
+            (home.sqft > 0 ? `<th>$/ft² per year</th>` : '') + (home.occupants > 0 ? `<th>$/person per year</th>` : '') +
+            `</tr></thead><tbody>`;
+        Object.entries(home.plans).forEach(([id, p]) => {
+            html += `<tr><td>${RATE_PLANS[id].name}</td>` +
+                (p.perSqftYear !== null ? `<td>$${p.perSqftYear.toFixed(3)}</td>` : '') +
+                (p.perPersonYear !== null ? `<td>$${p.perPersonYear.toFixed(2)}</td>` : '') + `</tr>`;
+        });
+        html += `</tbody></table>`;
+        document.getElementById('home-report').innerHTML = html;
+    }
+
+    function populateMonthPicker() {
+        const picker = document.getElementById('detail-month');
+        const keys = Object.keys(lastMonthlyUsage).sort();
+        picker.replaceChildren();
+        keys.forEach(key => {
+            const option = document.createElement('option');
+            option.value = key;
+            const missing = monthConfidence[key] ? monthConfidence[key].missing : 0;
+            option.textContent = missing > 0 ? `${key} (${formatHours(missing / 60)} missing)` : key;
+            picker.appendChild(option);
+        });
+        picker.value = keys[keys.length - 1];
+        renderMonthDetail(picker.value);
+    }
+
+    // "$1.50" / "-$1.50" for lines that can be credits
+    function formatSigned(amount) {
+        return `${amount < 0 ? '-' : ''}$${Math.abs(amount).toFixed(2)}`;
+    }
+
+    // User-typed text going into innerHTML
+    function escapeHtml(text) {
+        return text.replace(/[&<>"']/g, c => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' })[c]);
+    }
+
+    // 0.297868 -> "29.8¢/kWh"
+    function formatCents(rate) {
+        return `${(rate * 100).toFixed(1)}¢/kWh`;
+    }
+
+    // Tooltip for one R-30 summer block, from the current limits and rates
+    function describeSummerBlock(i) {
+        const { limits, rates } = ENERGY_RATES['r30'].summer;
+        let range = 'all usage';
+        if (limits.length > 0) {
+            if (i === 0) range = `<${limits[0]}kWh`;
+            else if (i < limits.length) range = `${limits[i - 1]}-${limits[i]}kWh`;
+            else range = `>${limits[limits.length - 1]}kWh`;
+        }
+        return `Summer ${range} (${formatCents(rates[Math.min(i, rates.length - 1)])})`;
+    }
+
+    // Bills a single month from the cached aggregates, so browsing months never re-reads the data
+    function renderMonthDetail(key) {
+        const usage = lastMonthlyUsage[key];
+        if (!usage) return;
+        const bills = billMonth(key, usage, { adjustments: billAdjustments });
+        const energyOf = b => BLOCK_KEYS.filter(k => k !== 'demand' && b[k] !== undefined).reduce((sum, k) => sum + b[k], 0);
+        const money = v => `$${v.toFixed(2)}`;
+
+        const adjusted = PLAN_IDS.some(id => bills[id].breakdown.kwhRiders || bills[id].breakdown.oneTime);
+        // The basic service charge is per day, so totals alone favor short months
+        const days = usage.days.size;
+
+        const [year, month] = key.split('-').map(Number);
+        const partial = days < daysInMonth(year, month - 1);
+
+        let html = `<p>${usage.total.toFixed(1)} kWh over ${days} day(s), ${(usage.total / days).toFixed(1)} kWh/day` +
+            (monthConfidence[key] ? ` (${describeConfidence(monthConfidence[key])})` : '') + `. ` +
+            `Per-day and per-30-day costs compare fairly across months of different lengths` +
+            `${partial ? ", though TOU-RD's demand charge is monthly, so its per-day cost runs high in a partial month like this one" : ''}.</p>`;
+        html += `<table class="month-table"><thead><tr><th>Plan</th><th>Energy</th><th>Demand</th><th>Fixed</th><th>Fuel</th>` +
+            `${adjusted ? '<th>Adjustments</th>' : ''}<th>Taxes & Fees</th><th>Total</th><th>Per Day</th><th>Per 30 Days</th></tr></thead><tbody>`;
+        PLAN_IDS.forEach(id => {
+            const b = bills[id].breakdown;
+            html += `<tr><td>${RATE_PLANS[id].name}</td><td>${money(energyOf(b))}</td><td>${money(b.demand || 0)}</td>` +
+                `<td>${money(b.fixed)}</td><td>${money(b.fcr)}</td>` +
+                (adjusted ? `<td>${formatSigned((b.kwhRiders || 0) + (b.oneTime || 0))}</td>` : '') +
+                `<td>${money(b.tax)}</td><td><strong>${money(bills[id].total)}</strong></td>` +
+                `<td>${money(bills[id].total / days)}</td><td>${money(bills[id].total / days * 30)}</td></tr>`;
+        });
+        html += `</tbody></table>`;
+        document.getElementById('month-detail').innerHTML = html;
+        renderBillAnatomy(key, usage);
+    }
+
+    // Step-by-step retelling of one plan's bill for the month, for reading rather than comparing
+    function renderBillAnatomy(key, usage) {
+        const list = document.getElementById('bill-anatomy');
+        const planId = document.getElementById('anatomy-plan').value;
+        if (!planId) {
+            list.classList.add('hidden');
+            return;
+        }
+        const { steps } = explainBill(key, usage, planId, { adjustments: billAdjustments });
+        list.innerHTML = steps.map(step => `<li><strong>${step.title}` +
+            `${step.amount !== undefined ? `: ${formatSigned(step.amount)}` : ''}</strong>` +
+            `<ul>${step.lines.map(line => `<li>${escapeHtml(line)}</li>`).join('')}</ul></li>`).join('');
+        list.classList.remove('hidden');
+    }
 });